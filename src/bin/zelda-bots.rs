@@ -0,0 +1,376 @@
+use clap::Parser;
+use lurk_lcsc::{
+    CharacterFlags, PktChangeRoom, PktCharacter, PktFight, PktLeave, PktLoot, PktStart, PktType,
+    Protocol, send_to,
+};
+use rand::Rng;
+use rand::seq::IndexedRandom;
+use std::collections::{BTreeMap, HashMap};
+use std::net::TcpStream;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+#[derive(Parser, Debug)]
+#[command(version, about = "Simulated Lurk clients for load testing ZeldaServer", long_about = None)]
+struct Args {
+    /// Host running the server
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+    /// Port the server is listening on
+    #[arg(short, long, default_value_t = 5051)]
+    port: u16,
+    /// Number of simulated clients
+    #[arg(short, long, default_value_t = 10)]
+    bots: usize,
+    /// How long to run, in seconds
+    #[arg(short, long, default_value_t = 60)]
+    duration: u64,
+    /// Pause between each bot's actions, in milliseconds
+    #[arg(long, default_value_t = 100)]
+    think_ms: u64,
+    #[command(flatten)]
+    verbosity: clap_verbosity_flag::Verbosity,
+}
+
+/// How long a bot waits for the server to answer before counting a timeout.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long a bot keeps reading follow-up packets after the first response.
+const DRAIN_TIMEOUT: Duration = Duration::from_millis(20);
+
+/// Latencies and failures collected by every bot.
+#[derive(Default)]
+struct Stats {
+    latencies: HashMap<&'static str, Vec<Duration>>,
+    errors: BTreeMap<String, usize>,
+    timeouts: usize,
+    failed_connections: usize,
+}
+
+/// What a bot knows about the room it is standing in.
+#[derive(Default)]
+struct View {
+    room: u16,
+    exits: Vec<u16>,
+    monsters: HashMap<Arc<str>, i16>,
+    looted: Vec<Arc<str>>,
+}
+
+impl View {
+    fn update(&mut self, pkt: &Protocol, errors: &mut BTreeMap<String, usize>) {
+        match pkt {
+            Protocol::Room(_, room) => {
+                self.room = room.room_number;
+                self.exits.clear();
+                self.monsters.clear();
+                self.looted.clear();
+            }
+            Protocol::Connection(_, conn) => {
+                self.exits.push(conn.room_number);
+            }
+            Protocol::Character(_, character)
+                if character.flags.contains(CharacterFlags::MONSTER)
+                    && character.current_room == self.room =>
+            {
+                if character.gold == 0 {
+                    self.looted.push(character.name.clone());
+                }
+                self.monsters
+                    .insert(character.name.clone(), character.health);
+            }
+            Protocol::Error(_, error) => {
+                *errors.entry(format!("{:?}", error.error)).or_default() += 1;
+            }
+            _ => {}
+        }
+    }
+
+    fn lootable(&self) -> Vec<Arc<str>> {
+        self.monsters
+            .iter()
+            .filter(|(name, health)| **health <= 0 && !self.looted.contains(name))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
+
+struct Bot {
+    stream: Arc<TcpStream>,
+    inbox: Receiver<Protocol>,
+    view: View,
+    stats: Stats,
+}
+
+impl Bot {
+    fn connect(address: &str) -> std::io::Result<Self> {
+        let stream = Arc::new(TcpStream::connect(address)?);
+        let (tx, inbox) = mpsc::channel();
+
+        let reader = stream.clone();
+        std::thread::spawn(move || {
+            while let Ok(pkt) = Protocol::recv(&reader) {
+                if tx.send(pkt).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            stream,
+            inbox,
+            view: View::default(),
+            stats: Stats::default(),
+        })
+    }
+
+    /// Wait for the first packet that isn't chat noise from other bots, record how long it
+    /// took under `kind`, then consume whatever else the server sent in response.
+    fn await_response(&mut self, kind: &'static str, sent: Instant) -> Option<Protocol> {
+        let first = loop {
+            let remaining = RESPONSE_TIMEOUT.saturating_sub(sent.elapsed());
+
+            match self.inbox.recv_timeout(remaining) {
+                Ok(Protocol::Message(_, _)) => continue,
+                Ok(pkt) => break pkt,
+                Err(RecvTimeoutError::Timeout) => {
+                    self.stats.timeouts += 1;
+                    return None;
+                }
+                Err(RecvTimeoutError::Disconnected) => return None,
+            }
+        };
+
+        self.stats
+            .latencies
+            .entry(kind)
+            .or_default()
+            .push(sent.elapsed());
+        self.view.update(&first, &mut self.stats.errors);
+
+        while let Ok(pkt) = self.inbox.recv_timeout(DRAIN_TIMEOUT) {
+            self.view.update(&pkt, &mut self.stats.errors);
+        }
+
+        Some(first)
+    }
+
+    fn character(&mut self, name: &str, initial_points: u16) -> bool {
+        let attack = initial_points / 2;
+        let defense = initial_points / 4;
+        let regen = initial_points - attack - defense;
+
+        let pkt = PktCharacter {
+            author: None,
+            packet_type: PktType::CHARACTER,
+            name: name.into(),
+            flags: CharacterFlags::empty(),
+            attack,
+            defense,
+            regen,
+            health: 0,
+            gold: 0,
+            current_room: 0,
+            description_len: 13,
+            description: "A load tester".into(),
+        };
+
+        let sent = Instant::now();
+        if send_to(&self.stream, &pkt).is_err() {
+            return false;
+        }
+
+        matches!(
+            self.await_response("Character", sent),
+            Some(Protocol::Accept(_, _))
+        )
+    }
+
+    fn start(&mut self) {
+        let sent = Instant::now();
+        if send_to(&self.stream, &PktStart::default()).is_ok() {
+            self.await_response("Start", sent);
+        }
+    }
+
+    fn wander(&mut self, rng: &mut impl Rng) {
+        let Some(&room_number) = self.view.exits.choose(rng) else {
+            return;
+        };
+
+        let pkt = PktChangeRoom {
+            packet_type: PktType::CHANGEROOM,
+            room_number,
+        };
+
+        let sent = Instant::now();
+        if send_to(&self.stream, &pkt).is_ok() {
+            self.await_response("ChangeRoom", sent);
+        }
+    }
+
+    fn fight(&mut self) {
+        let sent = Instant::now();
+        if send_to(&self.stream, &PktFight::default()).is_ok() {
+            self.await_response("Fight", sent);
+        }
+    }
+
+    fn loot(&mut self, rng: &mut impl Rng) {
+        let Some(target) = self.view.lootable().choose(rng).cloned() else {
+            return;
+        };
+
+        let pkt = PktLoot {
+            packet_type: PktType::LOOT,
+            target_name: target.as_ref().into(),
+        };
+
+        let sent = Instant::now();
+        if send_to(&self.stream, &pkt).is_ok() {
+            self.view.looted.push(target);
+            self.await_response("Loot", sent);
+        }
+    }
+}
+
+fn run_bot(id: usize, args: &Args, deadline: Instant) -> Stats {
+    let address = format!("{}:{}", args.host, args.port);
+
+    let mut bot = match Bot::connect(&address) {
+        Ok(bot) => bot,
+        Err(e) => {
+            warn!("Bot {} failed to connect: {}", id, e);
+            return Stats {
+                failed_connections: 1,
+                ..Stats::default()
+            };
+        }
+    };
+
+    // The server greets with Version then Game; Game tells us how many points we get
+    let initial_points = loop {
+        match bot.inbox.recv_timeout(RESPONSE_TIMEOUT) {
+            Ok(Protocol::Game(_, game)) => break game.initial_points,
+            Ok(_) => continue,
+            Err(_) => {
+                warn!("Bot {} never received the game description", id);
+                bot.stats.timeouts += 1;
+                return bot.stats;
+            }
+        }
+    };
+
+    if !bot.character(&format!("Bot {id}"), initial_points) {
+        warn!("Bot {} was not accepted", id);
+        return bot.stats;
+    }
+
+    bot.start();
+
+    let mut rng = rand::rng();
+    let think = Duration::from_millis(args.think_ms);
+
+    while Instant::now() < deadline {
+        match rng.random_range(0..100) {
+            0..50 => bot.wander(&mut rng),
+            50..85 => bot.fight(),
+            _ => bot.loot(&mut rng),
+        }
+
+        std::thread::sleep(think);
+    }
+
+    let _ = send_to(&bot.stream, &PktLeave::default());
+
+    bot.stats
+}
+
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    let idx = (sorted.len() * pct / 100).min(sorted.len() - 1);
+    sorted[idx]
+}
+
+fn report(stats: &Stats, args: &Args) {
+    println!(
+        "\n{} bot(s) for {}s against {}:{}\n",
+        args.bots, args.duration, args.host, args.port
+    );
+    println!(
+        "{:<12} {:>8} {:>10} {:>10} {:>10} {:>10}",
+        "Packet", "Count", "p50 (ms)", "p90 (ms)", "p99 (ms)", "max (ms)"
+    );
+
+    let mut kinds: Vec<_> = stats.latencies.iter().collect();
+    kinds.sort_by_key(|(kind, _)| **kind);
+
+    for (kind, latencies) in kinds {
+        let mut sorted = latencies.clone();
+        sorted.sort();
+
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+
+        println!(
+            "{:<12} {:>8} {:>10.2} {:>10.2} {:>10.2} {:>10.2}",
+            kind,
+            sorted.len(),
+            ms(percentile(&sorted, 50)),
+            ms(percentile(&sorted, 90)),
+            ms(percentile(&sorted, 99)),
+            ms(*sorted.last().expect("entries only exist once pushed")),
+        );
+    }
+
+    println!("\nErrors:");
+    if stats.errors.is_empty() {
+        println!("  none");
+    }
+    for (code, count) in &stats.errors {
+        println!("  {:<16} {}", code, count);
+    }
+
+    println!("\nTimeouts:           {}", stats.timeouts);
+    println!("Failed connections: {}", stats.failed_connections);
+}
+
+fn main() {
+    let args = Arc::new(Args::parse());
+
+    tracing_subscriber::fmt()
+        .with_max_level(args.verbosity)
+        .with_target(false)
+        .compact()
+        .init();
+
+    let deadline = Instant::now() + Duration::from_secs(args.duration);
+    let totals = Arc::new(Mutex::new(Stats::default()));
+
+    info!("Spawning {} bot(s)", args.bots);
+
+    let handles: Vec<_> = (0..args.bots)
+        .map(|id| {
+            let args = args.clone();
+            let totals = totals.clone();
+
+            std::thread::spawn(move || {
+                let stats = run_bot(id, &args, deadline);
+                let mut totals = totals.lock().unwrap();
+
+                for (kind, latencies) in stats.latencies {
+                    totals.latencies.entry(kind).or_default().extend(latencies);
+                }
+                for (code, count) in stats.errors {
+                    *totals.errors.entry(code).or_default() += count;
+                }
+                totals.timeouts += stats.timeouts;
+                totals.failed_connections += stats.failed_connections;
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let _ = handle.join();
+    }
+
+    report(&totals.lock().unwrap(), &args);
+}
@@ -0,0 +1,177 @@
+use chrono::{Local, NaiveDate};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use time::{UtcOffset, format_description::parse};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::fmt::time::OffsetTime;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{Layer, Registry};
+
+use crate::logic::config::LogConfig;
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Install the global tracing subscriber: stdout always, plus a rolling log file when
+/// `LOG_DIR` is set. Files are named `<prefix>_<date>.<n>.log`.
+pub fn init(level: LevelFilter, config: &LogConfig, file_prefix: &str) {
+    let timer = parse("[year]-[month padding:zero]-[day padding:zero] [hour]:[minute]:[second]")
+        .expect("Tracing time format is invalid");
+    let time_offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+    let timer = OffsetTime::new(time_offset, timer);
+
+    let mut layers: Vec<BoxedLayer> = vec![fmt_layer(io::stdout, config.json, true, timer.clone())];
+
+    if let Some(dir) = &config.dir {
+        let file = RollingFile::new(dir, file_prefix, config.max_bytes, config.max_files)
+            .expect("Failed to open log file");
+
+        layers.push(fmt_layer(Mutex::new(file), config.json, false, timer));
+    }
+
+    let filter = match &config.filter {
+        Some(filter) => filter.parse().expect("Failed to parse LOG_FILTER"),
+        None => Targets::new().with_default(level),
+    };
+
+    tracing_subscriber::registry()
+        .with(layers)
+        .with(filter)
+        .init();
+}
+
+fn fmt_layer<W>(
+    writer: W,
+    json: bool,
+    ansi: bool,
+    timer: OffsetTime<Vec<time::format_description::BorrowedFormatItem<'static>>>,
+) -> BoxedLayer
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_line_number(true)
+        .with_target(false)
+        .with_timer(timer)
+        .with_file(true)
+        .with_ansi(ansi);
+
+    if json {
+        layer.json().boxed()
+    } else {
+        layer.compact().boxed()
+    }
+}
+
+/// Log file that rolls over at midnight (local time) or once it exceeds `max_bytes`,
+/// keeping at most `max_files` files with the same prefix in the directory.
+pub struct RollingFile {
+    dir: PathBuf,
+    prefix: String,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    day: NaiveDate,
+    written: u64,
+}
+
+impl RollingFile {
+    pub fn new(dir: &Path, prefix: &str, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+
+        let day = Local::now().date_naive();
+        let file = Self::open_next(dir, prefix, day)?;
+
+        let rolling = Self {
+            dir: dir.to_path_buf(),
+            prefix: prefix.to_string(),
+            max_bytes,
+            max_files,
+            file,
+            day,
+            written: 0,
+        };
+        rolling.prune();
+
+        Ok(rolling)
+    }
+
+    /// Open the first unused `<prefix>_<day>.<n>.log` in `dir`.
+    fn open_next(dir: &Path, prefix: &str, day: NaiveDate) -> io::Result<File> {
+        let path = (0..)
+            .map(|index| dir.join(format!("{prefix}_{day}.{index}.log")))
+            .find(|path| !path.exists())
+            .expect("There is always an unused index");
+
+        File::options().create(true).append(true).open(path)
+    }
+
+    fn roll(&mut self) -> io::Result<()> {
+        self.day = Local::now().date_naive();
+        self.file = Self::open_next(&self.dir, &self.prefix, self.day)?;
+        self.written = 0;
+        self.prune();
+
+        Ok(())
+    }
+
+    /// Delete the oldest log files beyond the retention limit.
+    fn prune(&self) {
+        if self.max_files == 0 {
+            return;
+        }
+
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+
+        let mut logs: Vec<_> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                name.starts_with(&format!("{}_", self.prefix)) && name.ends_with(".log")
+            })
+            .filter_map(|entry| {
+                let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+                Some((modified, entry.path()))
+            })
+            .collect();
+
+        if logs.len() <= self.max_files {
+            return;
+        }
+
+        logs.sort();
+
+        for (_, path) in &logs[..logs.len() - self.max_files] {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+impl Write for RollingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let new_day = Local::now().date_naive() != self.day;
+        let too_big = self.max_bytes > 0
+            && self.written > 0
+            && self.written + buf.len() as u64 > self.max_bytes;
+
+        if new_day || too_big {
+            self.roll()?;
+        }
+
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
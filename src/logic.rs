@@ -1,34 +1,67 @@
 use lurk_lcsc::Protocol;
 use std::sync::mpsc::Sender;
 
+pub use crate::logic::client::{Client, MemoryTransport, Outbound, Transport};
 pub use crate::logic::commands::Action;
 pub use crate::logic::config::Config;
-pub use crate::logic::map::{Connection, Monster, Room};
-pub use crate::logic::state::GameState;
+pub use crate::logic::inventory::Inventory;
+pub use crate::logic::leveling::Progress;
+pub use crate::logic::map::{Connection, Item, ItemKind, Monster, Room, World};
+pub use crate::logic::state::{Channel, GameState};
+pub use crate::logic::targeting::TargetPacket;
 
+pub mod audit;
+pub mod behaviour;
+pub mod boss;
+pub mod client;
+pub mod combat;
 pub mod commands;
 pub mod config;
+pub mod death;
+pub mod distribution;
+pub mod effects;
+pub mod filter;
 pub mod handlers;
+pub mod inventory;
+pub mod leveling;
+pub mod loot_table;
+pub mod mail;
 pub mod map;
+pub mod metrics;
+pub mod names;
+pub mod party;
+pub mod record;
 pub mod state;
+pub mod targeting;
+
+#[cfg(test)]
+mod tests;
 
 pub enum ExtendedProtocol {
-    Base(Protocol),
+    Base(Client, Protocol),
     Command(Action),
+    /// Advance timed game events by one tick
+    Tick,
 }
 
 /// Type-safe wrapper around `Sender<ExtendedProtocol>`
 pub struct GameSender(pub Sender<ExtendedProtocol>);
 
 impl GameSender {
-    pub fn send_base(&self, pkt: Protocol) {
+    pub fn send_base(&self, client: Client, pkt: Protocol) {
         self.0
-            .send(ExtendedProtocol::Base(pkt))
+            .send(ExtendedProtocol::Base(client, pkt))
             .unwrap_or_else(|e| {
                 tracing::error!("Failed to send packet: {}", e);
             });
     }
 
+    pub fn send_tick(&self) {
+        self.0.send(ExtendedProtocol::Tick).unwrap_or_else(|e| {
+            tracing::error!("Failed to send tick: {}", e);
+        });
+    }
+
     pub fn send_cmd(&self, action: Action) {
         let action_str = action.to_string();
         self.0
@@ -0,0 +1,100 @@
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+use tracing::error;
+
+/// A gameplay event worth keeping after the debug logs have rolled away.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditEvent {
+    Character {
+        player: Arc<str>,
+        new_player: bool,
+        attack: u16,
+        defense: u16,
+        regen: u16,
+    },
+    Start {
+        player: Arc<str>,
+        room: u16,
+    },
+    Leave {
+        player: Arc<str>,
+        room: u16,
+    },
+    Kill {
+        monster: Arc<str>,
+        room: u16,
+        killer: Arc<str>,
+        participants: Vec<Arc<str>>,
+    },
+    Death {
+        player: Arc<str>,
+        room: u16,
+        killer: Arc<str>,
+    },
+    Loot {
+        player: Arc<str>,
+        monster: Arc<str>,
+        room: u16,
+        gold: u16,
+        items: Vec<Arc<str>>,
+    },
+    LevelUp {
+        player: Arc<str>,
+        level: u16,
+    },
+    Command {
+        operator: Box<str>,
+        kind: Box<str>,
+        argv: Vec<String>,
+    },
+}
+
+#[derive(Serialize)]
+struct Entry<'a> {
+    timestamp: String,
+    #[serde(flatten)]
+    event: &'a AuditEvent,
+}
+
+/// Append-only JSON lines file of [`AuditEvent`]s. Does nothing when disabled.
+pub struct AuditLog {
+    writer: Option<BufWriter<File>>,
+}
+
+impl AuditLog {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = File::options().create(true).append(true).open(path)?;
+
+        Ok(Self {
+            writer: Some(BufWriter::new(file)),
+        })
+    }
+
+    pub fn disabled() -> Self {
+        Self { writer: None }
+    }
+
+    pub fn record(&mut self, event: AuditEvent) {
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+
+        let entry = Entry {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            event: &event,
+        };
+
+        let result = serde_json::to_writer(&mut *writer, &entry)
+            .map_err(io::Error::from)
+            .and_then(|_| writer.write_all(b"\n"))
+            .and_then(|_| writer.flush());
+
+        if let Err(e) = result {
+            error!("Failed to write audit event: {}", e);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use lurk_lcsc::{CharacterFlags, PktCharacter};
+use rand::Rng;
+use rand::seq::IndexedRandom;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::logic::GameState;
+use crate::logic::combat::Blow;
+
+/// How a monster acts when nobody is fighting it.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Behaviour {
+    /// Stays put and only fights back
+    #[default]
+    Static,
+    /// Roams to a connected room every `WANDER_INTERVAL` ticks while unhurt
+    Wandering,
+    /// Attacks every player who enters its room
+    Aggressive,
+}
+
+impl GameState {
+    /// Move each unhurt wandering monster to a random connected room, once every
+    /// `wander_interval` ticks, and tell both rooms.
+    pub fn wander_monsters(&mut self) {
+        let interval = self.config.wander_interval;
+
+        if interval == 0 || !self.tick.is_multiple_of(interval) {
+            return;
+        }
+
+        // Sorted so a seeded RNG moves the same monsters the same way on replay
+        let mut wanderers: Vec<(u16, Arc<str>)> = self
+            .rooms
+            .values()
+            .flat_map(|room| room.monsters.iter().flatten())
+            .filter(|m| m.behaviour == Behaviour::Wandering && m.health >= m.max_health)
+            .map(|m| (m.current_room, m.name.clone()))
+            .collect();
+        wanderers.sort();
+
+        for (from, name) in wanderers {
+            let Some(room) = self.rooms.get(&from) else {
+                continue;
+            };
+
+            let mut exits: Vec<u16> = room.connections.keys().copied().collect();
+            exits.sort();
+
+            let Some(&to) = exits.choose(&mut self.rng) else {
+                continue;
+            };
+
+            self.move_monster(&name, from, to);
+        }
+    }
+
+    /// Take a monster out of one room and put it in another, alerting both.
+    fn move_monster(&mut self, name: &Arc<str>, from: u16, to: u16) {
+        let Some(title) = self.rooms.get(&to).map(|room| room.title.clone()) else {
+            error!("Room {} not found", to);
+            return;
+        };
+
+        let Some(mut monster) = self
+            .rooms
+            .get_mut(&from)
+            .and_then(|room| room.monsters.as_mut())
+            .and_then(|monsters| {
+                let idx = monsters.iter().position(|m| &m.name == name)?;
+                Some(monsters.remove(idx))
+            })
+        else {
+            error!("Monster '{}' isn't in room {}", name, from);
+            return;
+        };
+
+        info!("'{}' wandered from room {} to {}", name, from, to);
+
+        monster.current_room = to;
+        let pkt = PktCharacter::from(&monster);
+        self.rooms
+            .get_mut(&to)
+            .expect("destination was checked above")
+            .monsters
+            .get_or_insert_default()
+            .push(monster);
+
+        if let Some(room) = self.rooms.get(&from) {
+            self.message_room(room, format!("{name} wanders off to {title}."), true);
+            self.alert_room(room, &pkt);
+        }
+
+        if let Some(room) = self.rooms.get(&to) {
+            self.message_room(room, format!("{name} wanders in."), true);
+            self.alert_room(room, &pkt);
+        }
+    }
+
+    /// Let every living aggressive monster in a room strike a player who just walked in.
+    pub fn ambush(&mut self, name: &Arc<str>, room_id: u16) {
+        let Some(room) = self.rooms.get(&room_id) else {
+            return;
+        };
+
+        let attackers: Vec<_> = room
+            .monsters
+            .iter()
+            .flatten()
+            .filter(|m| m.behaviour == Behaviour::Aggressive && m.health > 0)
+            .cloned()
+            .collect();
+
+        for monster in attackers {
+            if !self.players.get(name).is_some_and(|p| p.flags.is_alive()) {
+                return;
+            }
+
+            let (blow, damage) = self.monster_strike(&monster, name);
+
+            let Some(player) = self.players.get_mut(name) else {
+                return;
+            };
+
+            info!(
+                "'{}' ambushed '{}' for {} damage",
+                monster.name, name, damage
+            );
+
+            let died = player.health <= 0;
+
+            if died {
+                player.flags.remove(CharacterFlags::ALIVE);
+            }
+
+            let player = player.clone();
+
+            // ================================================================================
+            // Tell the room, then carry over any effects or the player's death
+            // ================================================================================
+            if let Some(room) = self.rooms.get(&room_id) {
+                let message = match blow {
+                    Blow::Miss => blow.describe(&monster.name, name),
+                    _ => format!("{} lunges at {name} for {damage} damage!", monster.name),
+                };
+
+                self.message_room(room, message, true);
+                self.alert_room(room, &player);
+            }
+
+            if died {
+                self.kill_player(name, &monster.name);
+                return;
+            }
+
+            if blow != Blow::Miss {
+                for effect in &monster.inflicts {
+                    if self.rng.random_bool(effect.chance.clamp(0.0, 1.0)) {
+                        self.apply_effect(name, effect);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use lurk_lcsc::PktCharacter;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::logic::{GameState, Monster};
+
+/// Makes a monster a boss: it changes as its health drops through each phase.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Boss {
+    /// Phases in the order they are reached
+    pub phases: Vec<Phase>,
+    /// Phases entered so far
+    #[serde(skip)]
+    pub phase: usize,
+    /// Attack and defense before any phase, restored on revive
+    #[serde(skip)]
+    pub base: (u16, u16),
+}
+
+/// A stage of a boss fight, entered once the boss drops to a share of its max health.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Phase {
+    /// Percentage of max health at or below which the phase starts
+    pub below: u8,
+    /// Narrated to the room when the phase starts
+    pub narration: Box<str>,
+    pub attack: Option<u16>,
+    pub defense: Option<u16>,
+    /// Monsters summoned into the room when the phase starts
+    #[serde(default)]
+    pub minions: Vec<Minion>,
+}
+
+/// A monster a boss summons mid-fight.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Minion {
+    pub name: Arc<str>,
+    pub health: i16,
+    pub attack: u16,
+    pub defense: u16,
+    pub desc: Box<str>,
+    #[serde(default)]
+    pub xp: u32,
+}
+
+impl Monster {
+    /// Enter every phase the monster's health has dropped into, applying their stat
+    /// changes. Returns the phases entered.
+    pub fn enter_phases(&mut self) -> Vec<Phase> {
+        let Some(boss) = &mut self.boss else {
+            return Vec::new();
+        };
+
+        let mut entered = Vec::new();
+
+        while let Some(phase) = boss.phases.get(boss.phase) {
+            let threshold = i32::from(self.max_health) * i32::from(phase.below) / 100;
+
+            if i32::from(self.health) > threshold {
+                break;
+            }
+
+            self.attack = phase.attack.unwrap_or(self.attack);
+            self.defense = phase.defense.unwrap_or(self.defense);
+            boss.phase += 1;
+
+            entered.push(phase.clone());
+        }
+
+        entered
+    }
+
+    /// Put a boss back to its first phase.
+    pub fn reset_phases(&mut self) {
+        if let Some(boss) = &mut self.boss {
+            boss.phase = 0;
+            (self.attack, self.defense) = boss.base;
+        }
+    }
+}
+
+impl GameState {
+    /// Add a boss's minions to a room, numbering any whose name is taken. Returns their
+    /// characters for alerting the room.
+    pub fn spawn_minions(&mut self, room_id: u16, minions: &[Minion]) -> Vec<PktCharacter> {
+        let Some(room) = self.rooms.get_mut(&room_id) else {
+            error!("Room {} not found", room_id);
+            return Vec::new();
+        };
+
+        let monsters = room.monsters.get_or_insert_default();
+        let mut spawned = Vec::new();
+
+        for minion in minions {
+            let taken = |name: &str| monsters.iter().any(|m| m.name.as_ref() == name);
+            let name: Arc<str> = match taken(&minion.name) {
+                false => minion.name.clone(),
+                true => (2..)
+                    .map(|n| format!("{} {n}", minion.name))
+                    .find(|name| !taken(name))
+                    .expect("some number is free")
+                    .into(),
+            };
+
+            info!("Summoned '{}' into room {}", name, room_id);
+
+            monsters.push(Monster {
+                name,
+                current_room: room_id,
+                max_health: minion.health,
+                health: minion.health,
+                attack: minion.attack,
+                defense: minion.defense,
+                desc: minion.desc.clone(),
+                xp: minion.xp,
+                summoned: true,
+                ..Default::default()
+            });
+
+            spawned.push(PktCharacter::from(monsters.last().expect("just pushed")));
+        }
+
+        spawned
+    }
+}
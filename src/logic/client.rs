@@ -0,0 +1,182 @@
+use lurk_lcsc::{PktCharacter, PktConnection, PktError, PktMessage, PktRoom, PktType};
+use lurk_lcsc::{send_accept, send_error, send_message, send_room, send_to};
+use std::io;
+use std::net::{Shutdown, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::logic::metrics::METRICS;
+
+static NEXT_CLIENT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A packet the game state can deliver to a client.
+#[derive(Clone)]
+pub enum Outbound {
+    Accept(PktType),
+    Character(PktCharacter),
+    Connection(PktConnection),
+    Error(PktError),
+    Message(PktMessage),
+    Room(PktRoom),
+}
+
+impl std::fmt::Display for Outbound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outbound::Accept(accept_type) => write!(f, "Accept({:?})", accept_type),
+            Outbound::Character(pkt) => write!(f, "{}", pkt),
+            Outbound::Connection(pkt) => write!(f, "{}", pkt),
+            Outbound::Error(pkt) => write!(f, "{}", pkt),
+            Outbound::Message(pkt) => write!(f, "{}", pkt),
+            Outbound::Room(pkt) => write!(f, "{}", pkt),
+        }
+    }
+}
+
+/// Anything capable of delivering packets to a single connected client.
+pub trait Transport: Send + Sync {
+    fn send(&self, pkt: Outbound);
+    fn shutdown(&self) -> io::Result<()>;
+}
+
+/// Cheap, clonable handle to a connected client.
+/// Two handles are equal when they refer to the same connection.
+#[derive(Clone)]
+pub struct Client {
+    id: u64,
+    transport: Arc<dyn Transport>,
+}
+
+impl Client {
+    pub fn new(transport: impl Transport + 'static) -> Self {
+        Self {
+            id: NEXT_CLIENT_ID.fetch_add(1, Ordering::Relaxed),
+            transport: Arc::new(transport),
+        }
+    }
+
+    /// Wrap a plain TCP stream.
+    pub fn tcp(stream: Arc<TcpStream>) -> Self {
+        Self::new(TcpTransport(stream))
+    }
+
+    /// Create a client backed by memory, returning the transport so the caller can
+    /// inspect what the game state sent.
+    pub fn memory() -> (Self, MemoryTransport) {
+        let transport = MemoryTransport::default();
+        (Self::new(transport.clone()), transport)
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn send(&self, pkt: Outbound) {
+        METRICS.observe_outbound(&pkt);
+        self.transport.send(pkt);
+    }
+
+    pub fn send_accept(&self, accept_type: PktType) {
+        self.send(Outbound::Accept(accept_type));
+    }
+
+    pub fn send_character(&self, pkt: &PktCharacter) {
+        self.send(Outbound::Character(pkt.clone()));
+    }
+
+    pub fn send_connection(&self, pkt: PktConnection) {
+        self.send(Outbound::Connection(pkt));
+    }
+
+    pub fn send_error(&self, pkt: PktError) {
+        self.send(Outbound::Error(pkt));
+    }
+
+    pub fn send_message(&self, pkt: PktMessage) {
+        self.send(Outbound::Message(pkt));
+    }
+
+    pub fn send_room(&self, pkt: PktRoom) {
+        self.send(Outbound::Room(pkt));
+    }
+
+    pub fn shutdown(&self) -> io::Result<()> {
+        self.transport.shutdown()
+    }
+}
+
+impl PartialEq for Client {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Client {}
+
+impl std::fmt::Debug for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Client({})", self.id)
+    }
+}
+
+/// Plaintext Lurk over TCP.
+pub struct TcpTransport(pub Arc<TcpStream>);
+
+impl Transport for TcpTransport {
+    fn send(&self, pkt: Outbound) {
+        match pkt {
+            Outbound::Accept(accept_type) => {
+                send_accept!(self.0.clone(), accept_type);
+            }
+            Outbound::Character(pkt) => {
+                let _ = send_to(self.0.as_ref(), &pkt);
+            }
+            Outbound::Connection(pkt) => {
+                let _ = send_to(self.0.as_ref(), &pkt);
+            }
+            Outbound::Error(pkt) => {
+                send_error!(self.0.clone(), pkt);
+            }
+            Outbound::Message(pkt) => {
+                send_message!(self.0.clone(), pkt);
+            }
+            Outbound::Room(pkt) => {
+                send_room!(self.0.clone(), pkt);
+            }
+        }
+    }
+
+    fn shutdown(&self) -> io::Result<()> {
+        self.0.shutdown(Shutdown::Both)
+    }
+}
+
+/// Records outbound packets instead of writing them to a socket.
+/// Clones share the same outbox, so one can be kept while the other is handed to a `Client`.
+#[derive(Clone, Default)]
+pub struct MemoryTransport {
+    outbox: Arc<Mutex<Vec<Outbound>>>,
+    closed: Arc<AtomicBool>,
+}
+
+impl MemoryTransport {
+    /// Drain and return every packet sent since the last call.
+    pub fn take(&self) -> Vec<Outbound> {
+        std::mem::take(&mut *self.outbox.lock().unwrap())
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn send(&self, pkt: Outbound) {
+        self.outbox.lock().unwrap().push(pkt);
+    }
+
+    fn shutdown(&self) -> io::Result<()> {
+        self.closed.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+use lurk_lcsc::PktCharacter;
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+use tracing::error;
+
+use crate::logic::effects::EffectKind;
+use crate::logic::{GameState, Monster};
+
+/// Serde default for a chance, from 0 to 1, that always comes up.
+pub fn always() -> f64 {
+    1.0
+}
+
+/// Odds and damage formula for every blow struck in battle.
+#[derive(Debug, Clone)]
+pub struct CombatRules {
+    /// Seed for the combat RNG; a random one is picked when unset
+    pub seed: Option<u64>,
+    /// Chance, from 0 to 1, that a blow misses entirely
+    pub miss_chance: f64,
+    /// Chance, from 0 to 1, that a blow that lands is a critical hit
+    pub crit_chance: f64,
+    /// Damage multiplier for critical hits
+    pub crit_multiplier: f64,
+    /// Damage varies randomly by up to this fraction either way
+    pub damage_variance: f64,
+}
+
+/// The outcome of a single blow.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Blow {
+    Miss,
+    Hit(i16),
+    Crit(i16),
+}
+
+impl Blow {
+    pub fn damage(self) -> i16 {
+        match self {
+            Blow::Miss => 0,
+            Blow::Hit(damage) | Blow::Crit(damage) => damage,
+        }
+    }
+
+    /// How the blow is narrated to the room, e.g. "Link hits Deku Baba for 12 damage".
+    pub fn describe(self, striker: &str, target: &str) -> String {
+        match self {
+            Blow::Miss => format!("{striker} misses {target}!"),
+            Blow::Hit(damage) => format!("{striker} hits {target} for {damage} damage"),
+            Blow::Crit(damage) => {
+                format!("{striker} lands a critical hit on {target} for {damage} damage!")
+            }
+        }
+    }
+}
+
+impl Default for CombatRules {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            miss_chance: 0.0,
+            crit_chance: 0.0,
+            crit_multiplier: 1.0,
+            damage_variance: 0.0,
+        }
+    }
+}
+
+impl CombatRules {
+    pub fn load() -> Self {
+        let chance = |var: &str, default: f64| {
+            let value = env::var(var)
+                .map(|v| {
+                    v.parse()
+                        .unwrap_or_else(|_| panic!("Failed to parse {var}"))
+                })
+                .unwrap_or(default);
+
+            assert!(
+                (0.0..=1.0).contains(&value),
+                "{var} must be between 0 and 1, got {value}"
+            );
+
+            value
+        };
+
+        let seed = env::var("COMBAT_SEED")
+            .ok()
+            .map(|v| v.parse().expect("Failed to parse COMBAT_SEED"));
+        let crit_multiplier = env::var("CRIT_MULTIPLIER")
+            .map(|v| v.parse().expect("Failed to parse CRIT_MULTIPLIER"))
+            .unwrap_or(2.0);
+
+        Self {
+            seed,
+            miss_chance: chance("MISS_CHANCE", 0.05),
+            crit_chance: chance("CRIT_CHANCE", 0.05),
+            crit_multiplier,
+            damage_variance: chance("DAMAGE_VARIANCE", 0.2),
+        }
+    }
+
+    /// Roll one blow: `attack - defense`, scaled by a random factor within the damage
+    /// variance and by the crit multiplier on a critical hit.
+    pub fn roll(&self, rng: &mut StdRng, attack: u16, defense: u16) -> Blow {
+        if rng.random_bool(self.miss_chance) {
+            return Blow::Miss;
+        }
+
+        let base = f64::from(attack.saturating_sub(defense));
+        let variance = match self.damage_variance {
+            0.0 => 1.0,
+            v => rng.random_range(1.0 - v..=1.0 + v),
+        };
+        let crit = rng.random_bool(self.crit_chance);
+        let multiplier = if crit { self.crit_multiplier } else { 1.0 };
+
+        // `as` saturates, so huge rolls cap at i16 MAX
+        let damage = (base * variance * multiplier).round() as i16;
+
+        if crit {
+            Blow::Crit(damage)
+        } else {
+            Blow::Hit(damage)
+        }
+    }
+}
+
+/// Who a monster strikes back at after surviving a round of battle.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Retaliation {
+    /// Every living player in the battle
+    #[default]
+    All,
+    /// One living player in the battle, picked at random
+    Random,
+    /// The living player in the battle with the highest attack
+    Threat,
+}
+
+impl Retaliation {
+    /// Pick the players a monster counterattacks from those in the battle.
+    pub fn victims(
+        self,
+        participants: &[Arc<str>],
+        players: &HashMap<Arc<str>, PktCharacter>,
+        rng: &mut StdRng,
+    ) -> Vec<Arc<str>> {
+        let alive: Vec<&Arc<str>> = participants
+            .iter()
+            .filter(|name| players.get(*name).is_some_and(|p| p.flags.is_alive()))
+            .collect();
+
+        match self {
+            Retaliation::All => alive.into_iter().cloned().collect(),
+            Retaliation::Random => alive
+                .choose(rng)
+                .map(|&name| name.clone())
+                .into_iter()
+                .collect(),
+            Retaliation::Threat => alive
+                .into_iter()
+                .max_by_key(|name| (players[*name].attack, std::cmp::Reverse(*name)))
+                .cloned()
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+impl GameState {
+    /// Roll a monster's blow at a player, soften it by their shield and take it off their
+    /// health. Returns the blow and the damage dealt.
+    pub fn monster_strike(&mut self, monster: &Monster, name: &Arc<str>) -> (Blow, i16) {
+        let Some(player) = self.players.get_mut(name) else {
+            error!("Unable to find player '{}'", name);
+            return (Blow::Miss, 0);
+        };
+
+        let blow = self
+            .config
+            .combat
+            .roll(&mut self.rng, monster.attack, player.defense);
+        let shield = self
+            .effects
+            .get(name)
+            .map_or(0, |e| e.potency(EffectKind::Shield));
+        let damage = blow.damage().saturating_sub_unsigned(shield).max(0);
+
+        player.health = player.health.saturating_sub(damage);
+
+        (blow, damage)
+    }
+}
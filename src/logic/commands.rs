@@ -1,13 +1,16 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::io;
 use tracing::{error, info};
 
 use crate::logic::GameSender;
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Action {
     pub kind: Box<str>,
     pub argv: Vec<String>,
+    /// Who issued the command, for the audit log
+    #[serde(default)]
+    pub operator: Box<str>,
 }
 
 impl std::fmt::Display for Action {
@@ -24,6 +27,11 @@ impl std::fmt::Display for Action {
 pub fn input(sender: GameSender, prefix: String) -> ! {
     info!("Listening for commands with prefix: '{}'", prefix);
 
+    let operator: Box<str> = std::env::var("USER")
+        .map(|user| format!("console:{user}"))
+        .unwrap_or_else(|_| "console".into())
+        .into();
+
     loop {
         // Take input from the console.
         let mut input = String::new();
@@ -47,7 +55,11 @@ pub fn input(sender: GameSender, prefix: String) -> ! {
         let argv: Vec<String> = input.split_whitespace().map(|s| s.to_string()).collect();
 
         let kind = argv[0].to_ascii_lowercase().into();
-        let action = Action { kind, argv };
+        let action = Action {
+            kind,
+            argv,
+            operator: operator.clone(),
+        };
 
         sender.send_cmd(action);
     }
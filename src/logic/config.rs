@@ -3,6 +3,11 @@ use std::env;
 use std::path::Path;
 use tracing::info;
 
+use crate::logic::combat::{CombatRules, Retaliation};
+use crate::logic::distribution::LootMode;
+use crate::logic::filter::ContentFilter;
+use crate::logic::targeting::TargetPacket;
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub cmd_prefix: Box<str>,
@@ -13,6 +18,83 @@ pub struct Config {
     pub major_rev: u8,
     pub minor_rev: u8,
     pub help_cmd: Box<str>,
+    pub tls_cert_path: Option<Box<Path>>,
+    pub tls_key_path: Option<Box<Path>>,
+    pub metrics_addr: Option<Box<str>>,
+    pub audit_path: Option<Box<Path>>,
+    /// Messages sent to this name are chat commands for the server
+    pub narrator_name: Box<str>,
+    /// Most messages held for an offline player
+    pub mailbox_cap: usize,
+    pub filter: ContentFilter,
+    pub name_min_length: usize,
+    pub name_max_length: usize,
+    /// Names no player may take, compared case-insensitively
+    pub reserved_names: Vec<Box<str>>,
+    /// Total XP needed to reach level 2, 3, and so on
+    pub xp_thresholds: Vec<u32>,
+    /// Stat points granted on each level up
+    pub points_per_level: u16,
+    /// Fraction of a player's gold left on their corpse when they die
+    pub death_gold_penalty: f32,
+    /// Seconds a dead player waits before respawning
+    pub respawn_delay: u64,
+    pub respawn_health: i16,
+    /// Lurk packet that may also pick a player's fight target
+    pub target_packet: TargetPacket,
+    /// Who monsters strike back at after each round of battle
+    pub retaliation: Retaliation,
+    pub combat: CombatRules,
+    /// Ticks between wandering monsters' moves; 0 keeps them still
+    pub wander_interval: u64,
+    /// Who may loot a defeated monster
+    pub loot_mode: LootMode,
+    /// Most players a party can hold
+    pub party_max_size: usize,
+    /// Only the attacker's party joins a fight, rather than everyone in battle in the room
+    pub party_battles: bool,
+}
+
+/// Logging settings. Loaded separately from [`Config`] since tracing has to be set up
+/// before anything else is logged.
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    /// Emit JSON lines instead of the compact human-readable format
+    pub json: bool,
+    /// Per-module level filters, e.g. `info,ZeldaServer::logic::handlers=debug`
+    pub filter: Option<Box<str>>,
+    /// Directory for rolling log files; file logging is off when unset
+    pub dir: Option<Box<Path>>,
+    /// Roll to a new file once the current one reaches this many bytes; 0 disables
+    pub max_bytes: u64,
+    /// Number of log files to keep; 0 keeps everything
+    pub max_files: usize,
+}
+
+impl LogConfig {
+    pub fn load() -> Self {
+        let json = match env::var("LOG_FORMAT").as_deref() {
+            Ok("json") => true,
+            Ok("compact") | Err(_) => false,
+            Ok(other) => panic!("LOG_FORMAT must be 'json' or 'compact', got '{other}'"),
+        };
+        let filter = env::var("LOG_FILTER").ok().map(|filter| filter.into());
+        let dir = env::var("LOG_DIR").ok().map(|dir| Path::new(&dir).into());
+        let max_bytes = env::var("LOG_MAX_BYTES")
+            .map(|v| v.parse().expect("Failed to parse LOG_MAX_BYTES"))
+            .unwrap_or(10 * 1024 * 1024);
+        let max_files = env::var("LOG_MAX_FILES")
+            .map(|v| v.parse().expect("Failed to parse LOG_MAX_FILES"))
+            .unwrap_or(14);
+
+        LogConfig {
+            json,
+            filter,
+            dir,
+            max_bytes,
+            max_files,
+        }
+    }
 }
 
 impl Config {
@@ -47,8 +129,98 @@ impl Config {
                 ${CMD_PREFIX}broadcast <content>            - Send a message to all players
                 ${CMD_PREFIX}message <recipient> <content>  - Send a private message to a player
                 ${CMD_PREFIX}nuke                           - Remove all disconnected players on the map
+                ${CMD_PREFIX}inventory <player>             - Show what a player is carrying
+                ${CMD_PREFIX}items                          - List item definitions and items on the floor
+                ${CMD_PREFIX}mail [player]                  - Count queued mail, or show a player's mailbox
                 ${CMD_PREFIX}revive                         - Revive all monsters on the map"
         }.replace("${CMD_PREFIX}", &cmd_prefix).into();
+        let tls_cert_path = env::var("TLS_CERT_FILEPATH")
+            .ok()
+            .map(|path| Path::new(&path).into());
+        let tls_key_path = env::var("TLS_KEY_FILEPATH")
+            .ok()
+            .map(|path| Path::new(&path).into());
+        let metrics_addr = env::var("METRICS_ADDR").ok().map(|addr| addr.into());
+        let audit_path = env::var("AUDIT_FILEPATH")
+            .ok()
+            .map(|path| Path::new(&path).into());
+
+        let narrator_name = env::var("NARRATOR_NAME").unwrap_or_else(|_| "Narrator".into());
+        let mailbox_cap = env::var("MAILBOX_CAP")
+            .map(|v| v.parse().expect("Failed to parse MAILBOX_CAP"))
+            .unwrap_or(20);
+
+        let name_min_length = env::var("NAME_MIN_LENGTH")
+            .map(|v| v.parse().expect("Failed to parse NAME_MIN_LENGTH"))
+            .unwrap_or(2);
+        let name_max_length = env::var("NAME_MAX_LENGTH")
+            .map(|v| v.parse().expect("Failed to parse NAME_MAX_LENGTH"))
+            .unwrap_or(32); // Lurk name fields are 32 bytes
+        let reserved_names = env::var("RESERVED_NAMES")
+            .unwrap_or_else(|_| "Server,Admin".into())
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| name.into())
+            .collect();
+
+        let xp_thresholds = env::var("XP_THRESHOLDS")
+            .unwrap_or_else(|_| "100,250,450,700,1000,1400,1900,2500,3200,4000".into())
+            .split(',')
+            .map(|xp| xp.trim().parse().expect("Failed to parse XP_THRESHOLDS"))
+            .collect();
+        let points_per_level = env::var("POINTS_PER_LEVEL")
+            .map(|v| v.parse().expect("Failed to parse POINTS_PER_LEVEL"))
+            .unwrap_or(10);
+
+        let death_gold_penalty = env::var("DEATH_GOLD_PENALTY")
+            .map(|v| v.parse().expect("Failed to parse DEATH_GOLD_PENALTY"))
+            .unwrap_or(0.5);
+        let respawn_delay = env::var("RESPAWN_DELAY")
+            .map(|v| v.parse().expect("Failed to parse RESPAWN_DELAY"))
+            .unwrap_or(10);
+        let respawn_health = env::var("RESPAWN_HEALTH")
+            .map(|v| v.parse().expect("Failed to parse RESPAWN_HEALTH"))
+            .unwrap_or(100);
+
+        let target_packet = match env::var("TARGET_PACKET").as_deref() {
+            Ok("none") | Err(_) => TargetPacket::None,
+            Ok("loot") => TargetPacket::Loot,
+            Ok("pvp_fight") => TargetPacket::PvpFight,
+            Ok(other) => {
+                panic!("TARGET_PACKET must be 'none', 'loot' or 'pvp_fight', got '{other}'")
+            }
+        };
+
+        let retaliation = match env::var("MONSTER_RETALIATION").as_deref() {
+            Ok("all") | Err(_) => Retaliation::All,
+            Ok("random") => Retaliation::Random,
+            Ok("threat") => Retaliation::Threat,
+            Ok(other) => {
+                panic!("MONSTER_RETALIATION must be 'all', 'random' or 'threat', got '{other}'")
+            }
+        };
+
+        let wander_interval = env::var("WANDER_INTERVAL")
+            .map(|v| v.parse().expect("Failed to parse WANDER_INTERVAL"))
+            .unwrap_or(30);
+
+        let loot_mode = match env::var("LOOT_MODE").as_deref() {
+            Ok("free_for_all") | Err(_) => LootMode::FreeForAll,
+            Ok("killer") => LootMode::KillerOnly,
+            Ok("split") => LootMode::SplitByDamage,
+            Ok("round_robin") => LootMode::RoundRobin,
+            Ok(other) => panic!(
+                "LOOT_MODE must be 'free_for_all', 'killer', 'split' or 'round_robin', got '{other}'"
+            ),
+        };
+
+        let party_max_size = env::var("PARTY_MAX_SIZE")
+            .map(|v| v.parse().expect("Failed to parse PARTY_MAX_SIZE"))
+            .unwrap_or(4);
+        let party_battles = env::var("PARTY_BATTLES")
+            .map(|v| v.parse().expect("Failed to parse PARTY_BATTLES"))
+            .unwrap_or(true);
 
         info!("Successfully loaded configuration!");
 
@@ -61,6 +233,28 @@ impl Config {
             major_rev,
             minor_rev,
             help_cmd,
+            tls_cert_path,
+            tls_key_path,
+            metrics_addr,
+            audit_path,
+            narrator_name: narrator_name.into(),
+            mailbox_cap,
+            filter: ContentFilter::load(),
+            name_min_length,
+            name_max_length,
+            reserved_names,
+            xp_thresholds,
+            points_per_level,
+            death_gold_penalty,
+            respawn_delay,
+            respawn_health,
+            target_packet,
+            retaliation,
+            combat: CombatRules::load(),
+            wander_interval,
+            loot_mode,
+            party_max_size,
+            party_battles,
         }
     }
 }
@@ -0,0 +1,191 @@
+use lurk_lcsc::{CharacterFlags, LurkError, PktCharacter, PktError, PktRoom};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::logic::audit::AuditEvent;
+use crate::logic::{Client, GameState, Monster};
+
+/// Room players respawn in.
+const RESPAWN_ROOM: u16 = 0;
+/// Appended to a player's name to name their corpse
+const CORPSE_SUFFIX: &str = "'s corpse";
+/// Lurk name fields are 32 bytes
+const NAME_BYTES: usize = 32;
+
+/// Name of a player's corpse, shortening long names so it still fits a Lurk name field.
+pub fn corpse_name(name: &str) -> String {
+    let mut end = name.len().min(NAME_BYTES - CORPSE_SUFFIX.len());
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    format!("{}{CORPSE_SUFFIX}", &name[..end])
+}
+
+impl GameState {
+    /// Check that a player is alive. Sends an error to the author if not.
+    /// Returns `true` if the player is alive.
+    pub fn ensure_alive(player: &PktCharacter, author: &Client) -> bool {
+        if !player.flags.is_alive() {
+            author.send_error(PktError::new(
+                LurkError::OTHER,
+                "You are dead! Wait to respawn, or message the narrator /respawn.",
+            ));
+            return false;
+        }
+        true
+    }
+
+    /// Mark a player dead, audit the death, leave part of their gold on a corpse in the
+    /// room and schedule their respawn.
+    pub fn kill_player(&mut self, name: &Arc<str>, killer: &str) {
+        let penalty = self.config.death_gold_penalty;
+        let respawn_at = self.tick + self.config.respawn_delay;
+
+        let Some(player) = self.players.get_mut(name) else {
+            error!("Unable to find player '{}'", name);
+            return;
+        };
+
+        player.flags = CharacterFlags::dead() | CharacterFlags::STARTED;
+        player.health = 0;
+
+        let dropped = (player.gold as f32 * penalty).round() as u16;
+        player.gold -= dropped.min(player.gold);
+
+        let current_room = player.current_room;
+        let player = player.clone();
+
+        info!("'{}' died, dropping {} gold", name, dropped);
+
+        self.audit.record(AuditEvent::Death {
+            player: name.clone(),
+            room: current_room,
+            killer: killer.into(),
+        });
+
+        self.respawns.insert(name.clone(), respawn_at);
+        self.effects.remove(name);
+
+        // ================================================================================
+        // Leave the dropped gold on a corpse anyone in the room can loot
+        // ================================================================================
+        let corpse = (dropped > 0)
+            .then(|| self.drop_corpse(name, current_room, dropped))
+            .flatten();
+
+        let Some(room) = self.rooms.get(&current_room) else {
+            error!("Player isn't in a valid room");
+            return;
+        };
+
+        self.message_room(room, format!("{name} was slain by {killer}!"), true);
+        self.alert_room(room, &player);
+
+        if let Some(corpse) = &corpse {
+            self.alert_room(room, corpse);
+        }
+
+        if let Some(author) = self.clients.get(name) {
+            self.narrate(
+                author,
+                name,
+                &format!(
+                    "You died and dropped {dropped} gold. You will respawn in {} second(s), \
+                     or message the narrator /respawn to go now.",
+                    self.config.respawn_delay
+                ),
+            );
+        }
+    }
+
+    /// Add gold to a player's corpse in a room, creating it if needed. Corpses are dead
+    /// monsters with no max health, so they can be looted but never revived.
+    fn drop_corpse(&mut self, name: &str, room: u16, gold: u16) -> Option<PktCharacter> {
+        let monsters = self.rooms.get_mut(&room)?.monsters.get_or_insert_default();
+        let corpse_name = corpse_name(name);
+
+        let corpse = match monsters
+            .iter_mut()
+            .position(|m| m.name.as_ref() == corpse_name)
+        {
+            Some(idx) => &mut monsters[idx],
+            None => {
+                monsters.push(Monster {
+                    name: corpse_name.into(),
+                    current_room: room,
+                    desc: format!("The remains of {name}.").into(),
+                    ..Default::default()
+                });
+
+                monsters.last_mut().expect("just pushed")
+            }
+        };
+
+        corpse.gold = corpse.gold.saturating_add(gold);
+
+        Some(PktCharacter::from(corpse))
+    }
+
+    /// Bring a dead player back to life in the starting room with restored health.
+    pub fn respawn(&mut self, name: &Arc<str>) {
+        self.respawns.remove(name);
+
+        let health = self.config.respawn_health;
+
+        let Some(player) = self.players.get_mut(name) else {
+            error!("Unable to find player '{}'", name);
+            return;
+        };
+
+        if player.flags.is_alive() {
+            return;
+        }
+
+        let old_room = player.current_room;
+
+        player.flags = CharacterFlags::alive() | CharacterFlags::STARTED;
+        player.health = health;
+        player.current_room = RESPAWN_ROOM;
+
+        info!("'{}' respawned", name);
+
+        if let Some(room) = self.rooms.get_mut(&old_room) {
+            room.players.retain(|other| other != name);
+        }
+
+        if let Some(room) = self.rooms.get_mut(&RESPAWN_ROOM) {
+            room.players.insert(name.clone());
+        }
+
+        // ================================================================================
+        // Alert both rooms and send the respawned player their surroundings
+        // ================================================================================
+        let Some(player) = self.players.get(name) else {
+            return;
+        };
+
+        if let Some(room) = self.rooms.get(&old_room) {
+            self.alert_room(room, player);
+        }
+
+        if let Some(room) = self.rooms.get(&RESPAWN_ROOM) {
+            self.message_room(room, format!("{name} has returned to the living."), true);
+            self.alert_room(room, player);
+        }
+
+        let Some(author) = self.clients.get(name).cloned() else {
+            return;
+        };
+
+        if let Some(room) = self.rooms.get(&RESPAWN_ROOM) {
+            author.send_room(PktRoom::from(room));
+        }
+
+        self.send_connections(&author, RESPAWN_ROOM);
+
+        if let Some(room) = self.rooms.get(&RESPAWN_ROOM) {
+            self.send_room_contents(&author, room);
+        }
+    }
+}
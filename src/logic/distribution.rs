@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Who may loot a monster once it has been defeated.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LootMode {
+    /// Whoever loots first takes everything
+    #[default]
+    FreeForAll,
+    /// Only the player who landed the killing blow
+    KillerOnly,
+    /// Anyone who damaged it; the gold is split by damage dealt
+    SplitByDamage,
+    /// Each kill's loot goes to the next fighter in turn
+    RoundRobin,
+}
+
+impl LootMode {
+    /// The players allowed to loot a monster that just died. Empty means anyone may.
+    /// `turns` counts kills by each group of fighters, keyed by their sorted names, for
+    /// round-robin and is advanced by it.
+    pub fn looters(
+        self,
+        participants: &[Arc<str>],
+        killer: &Arc<str>,
+        damage: &HashMap<Arc<str>, u32>,
+        turns: &mut HashMap<Vec<Arc<str>>, u64>,
+    ) -> Vec<Arc<str>> {
+        match self {
+            LootMode::FreeForAll => Vec::new(),
+            LootMode::KillerOnly => vec![killer.clone()],
+            LootMode::SplitByDamage => {
+                let mut dealt: Vec<Arc<str>> = damage
+                    .iter()
+                    .filter(|(_, dealt)| **dealt > 0)
+                    .map(|(name, _)| name.clone())
+                    .collect();
+
+                if dealt.is_empty() {
+                    dealt.push(killer.clone());
+                }
+
+                dealt
+            }
+            LootMode::RoundRobin => {
+                let mut order = participants.to_vec();
+                order.sort();
+
+                let turn = turns.entry(order.clone()).or_default();
+
+                let Some(next) = order.get((*turn % order.len().max(1) as u64) as usize) else {
+                    return vec![killer.clone()];
+                };
+
+                *turn += 1;
+
+                vec![next.clone()]
+            }
+        }
+    }
+}
+
+/// Split looted gold between the players who damaged a monster, in proportion to the
+/// damage each dealt. The looter keeps whatever doesn't divide evenly.
+pub fn split_gold(
+    gold: u16,
+    looter: &Arc<str>,
+    damage: &HashMap<Arc<str>, u32>,
+) -> Vec<(Arc<str>, u16)> {
+    let total: u64 = damage.values().map(|&dealt| u64::from(dealt)).sum();
+
+    if total == 0 {
+        return vec![(looter.clone(), gold)];
+    }
+
+    let mut shares: Vec<(Arc<str>, u16)> = damage
+        .iter()
+        .filter(|(name, _)| *name != looter)
+        .map(|(name, &dealt)| {
+            let share = u64::from(gold) * u64::from(dealt) / total;
+            (name.clone(), share as u16)
+        })
+        .filter(|(_, share)| *share > 0)
+        .collect();
+
+    let given: u16 = shares.iter().map(|(_, share)| share).sum();
+    shares.push((looter.clone(), gold - given));
+
+    shares
+}
+
+/// Split looted gold evenly between party members in the room. The looter keeps whatever
+/// doesn't divide evenly.
+pub fn split_evenly(gold: u16, looter: &Arc<str>, members: &[Arc<str>]) -> Vec<(Arc<str>, u16)> {
+    let others: Vec<&Arc<str>> = members.iter().filter(|name| *name != looter).collect();
+    let each = gold / (others.len() as u16 + 1);
+
+    let mut shares: Vec<(Arc<str>, u16)> = others
+        .into_iter()
+        .filter(|_| each > 0)
+        .map(|name| (name.clone(), each))
+        .collect();
+
+    let given: u16 = shares.iter().map(|(_, share)| share).sum();
+    shares.push((looter.clone(), gold - given));
+
+    shares
+}
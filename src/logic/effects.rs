@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::logic::GameState;
+use crate::logic::combat::always;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum EffectKind {
+    /// Lose `potency` health every tick
+    Poison,
+    /// Unable to attack
+    Stun,
+    /// Gain `potency` health every tick
+    Regen,
+    /// Blows taken deal `potency` less damage
+    Shield,
+}
+
+impl EffectKind {
+    /// How the effect reads in a sentence, e.g. "You are poisoned".
+    fn adjective(self) -> &'static str {
+        match self {
+            EffectKind::Poison => "poisoned",
+            EffectKind::Stun => "stunned",
+            EffectKind::Regen => "regenerating",
+            EffectKind::Shield => "shielded",
+        }
+    }
+}
+
+/// A status effect as defined in the map, inflicted by a monster's blows or applied by
+/// using an item.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Effect {
+    pub kind: EffectKind,
+    #[serde(default)]
+    pub potency: u16,
+    /// Ticks the effect lasts
+    pub duration: u32,
+    /// Chance, from 0 to 1, that a monster's blow inflicts it; items always apply it
+    #[serde(default = "always")]
+    pub chance: f64,
+}
+
+/// An effect currently on a player.
+#[derive(Clone, Debug)]
+pub struct ActiveEffect {
+    pub kind: EffectKind,
+    pub potency: u16,
+    /// Ticks left before it wears off
+    pub remaining: u32,
+}
+
+/// The effects currently on a player. Stored alongside the player's PktCharacter in
+/// GameState.
+#[derive(Default, Clone, Debug)]
+pub struct StatusEffects {
+    pub active: Vec<ActiveEffect>,
+}
+
+impl StatusEffects {
+    /// Start an effect. Reapplying one already active keeps the stronger potency and the
+    /// longer duration rather than stacking.
+    pub fn add(&mut self, effect: &Effect) {
+        match self.active.iter_mut().find(|a| a.kind == effect.kind) {
+            Some(active) => {
+                active.potency = active.potency.max(effect.potency);
+                active.remaining = active.remaining.max(effect.duration);
+            }
+            None => self.active.push(ActiveEffect {
+                kind: effect.kind,
+                potency: effect.potency,
+                remaining: effect.duration,
+            }),
+        }
+    }
+
+    pub fn has(&self, kind: EffectKind) -> bool {
+        self.active.iter().any(|a| a.kind == kind)
+    }
+
+    /// Potency of an active effect, or 0 if it isn't active.
+    pub fn potency(&self, kind: EffectKind) -> u16 {
+        self.active
+            .iter()
+            .find(|a| a.kind == kind)
+            .map_or(0, |a| a.potency)
+    }
+}
+
+impl GameState {
+    /// Put an effect on a player and tell them about it.
+    pub fn apply_effect(&mut self, name: &Arc<str>, effect: &Effect) {
+        if !self.players.contains_key(name) {
+            error!("Unable to find player '{}'", name);
+            return;
+        }
+
+        info!(
+            "'{}' is {} for {} tick(s)",
+            name,
+            effect.kind.adjective(),
+            effect.duration
+        );
+
+        self.effects.entry(name.clone()).or_default().add(effect);
+
+        if let Some(author) = self.clients.get(name) {
+            self.narrate(
+                author,
+                name,
+                &format!("You are {}!", effect.kind.adjective()),
+            );
+        }
+    }
+
+    /// Run one tick of every player's effects: poison hurts, regen heals and each effect
+    /// counts down, wearing off when it runs out.
+    pub fn tick_effects(&mut self) {
+        let names: Vec<Arc<str>> = self.effects.keys().cloned().collect();
+
+        for name in names {
+            let Some(player) = self.players.get_mut(&name) else {
+                continue;
+            };
+
+            if !player.flags.is_alive() {
+                continue;
+            }
+
+            let effects = self.effects.entry(name.clone()).or_default();
+            let poison = effects.potency(EffectKind::Poison) as i16;
+            let regen = effects.potency(EffectKind::Regen) as i16;
+
+            player.health = player.health.saturating_sub(poison).saturating_add(regen);
+
+            let died = player.health <= 0;
+            let expired: Vec<EffectKind> = effects
+                .active
+                .iter_mut()
+                .filter_map(|a| {
+                    a.remaining = a.remaining.saturating_sub(1);
+                    (a.remaining == 0).then_some(a.kind)
+                })
+                .collect();
+
+            effects.active.retain(|a| a.remaining > 0);
+
+            if effects.active.is_empty() {
+                self.effects.remove(&name);
+            }
+
+            // ================================================================================
+            // Let the room see the new health and tell the player what wore off
+            // ================================================================================
+            if poison != regen {
+                let player = &self.players[&name];
+
+                if let Some(room) = self.rooms.get(&player.current_room) {
+                    self.alert_room(room, player);
+                }
+            }
+
+            if died {
+                info!("'{}' succumbed to poison", name);
+                self.kill_player(&name, "poison");
+                continue;
+            }
+
+            if let Some(author) = self.clients.get(&name) {
+                for kind in expired {
+                    self.narrate(
+                        author,
+                        &name,
+                        &format!("You are no longer {}.", kind.adjective()),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Describe a player's active effects, e.g. "You are poisoned (3 tick(s) left)."
+    pub fn describe_effects(&self, name: &Arc<str>) -> String {
+        let Some(effects) = self.effects.get(name).filter(|e| !e.active.is_empty()) else {
+            return String::from("You feel normal.");
+        };
+
+        let list: Vec<String> = effects
+            .active
+            .iter()
+            .map(|a| format!("{} ({} tick(s) left)", a.kind.adjective(), a.remaining))
+            .collect();
+
+        format!("You are {}.", list.join(", "))
+    }
+}
@@ -0,0 +1,199 @@
+use regex::Regex;
+use std::borrow::Cow;
+use std::env;
+use tracing::{info, warn};
+
+/// What to do with text that breaks a filter rule.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum FilterMode {
+    /// Replace offending words with `*`, squash repeats and truncate
+    #[default]
+    Mask,
+    /// Refuse the packet with a `PktError`
+    Reject,
+    /// Let it through but log a warning
+    Log,
+}
+
+/// Profanity and spam filter applied to player messages, names and descriptions.
+/// An empty filter lets everything through.
+#[derive(Debug, Clone, Default)]
+pub struct ContentFilter {
+    pub mode: FilterMode,
+    /// Banned words, matched whole and case-insensitively
+    pub words: Option<Regex>,
+    pub rules: Vec<Regex>,
+    /// Longest run of the same character allowed
+    pub max_repeat: Option<usize>,
+    /// Longest text allowed, in characters
+    pub max_length: Option<usize>,
+}
+
+impl ContentFilter {
+    pub fn load() -> Self {
+        let mode = match env::var("FILTER_MODE").as_deref() {
+            Ok("mask") | Err(_) => FilterMode::Mask,
+            Ok("reject") => FilterMode::Reject,
+            Ok("log") => FilterMode::Log,
+            Ok(other) => panic!("FILTER_MODE must be 'mask', 'reject' or 'log', got '{other}'"),
+        };
+        let words = env::var("FILTER_WORDS_FILEPATH").ok().map(|path| {
+            let list = std::fs::read_to_string(&path).expect("Failed to read filter word list!");
+            Self::word_pattern(list.lines())
+        });
+        let rules = env::var("FILTER_RULES_FILEPATH")
+            .ok()
+            .map(|path| {
+                let list = std::fs::read_to_string(&path).expect("Failed to read filter rules!");
+
+                list.lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(|rule| Regex::new(rule).expect("Failed to parse filter rule"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let max_repeat = env::var("FILTER_MAX_REPEAT")
+            .ok()
+            .map(|v| v.parse().expect("Failed to parse FILTER_MAX_REPEAT"));
+        let max_length = env::var("FILTER_MAX_LENGTH")
+            .ok()
+            .map(|v| v.parse().expect("Failed to parse FILTER_MAX_LENGTH"));
+
+        let filter = ContentFilter {
+            mode,
+            words: words.flatten(),
+            rules,
+            max_repeat,
+            max_length,
+        };
+
+        info!(
+            "Content filter: {:?} mode, {} rule(s)",
+            filter.mode,
+            filter.rules.len() + filter.words.is_some() as usize
+        );
+
+        filter
+    }
+
+    /// Build one case-insensitive, whole-word pattern from a word list, ignoring blank
+    /// lines and `#` comments. Returns `None` for an empty list.
+    pub fn word_pattern<'a>(words: impl Iterator<Item = &'a str>) -> Option<Regex> {
+        let words: Vec<String> = words
+            .map(str::trim)
+            .filter(|word| !word.is_empty() && !word.starts_with('#'))
+            .map(regex::escape)
+            .collect();
+
+        if words.is_empty() {
+            return None;
+        }
+
+        let pattern = format!(r"(?i)\b(?:{})\b", words.join("|"));
+
+        Some(Regex::new(&pattern).expect("Escaped words always form a valid pattern"))
+    }
+
+    /// Check `text` against every rule. In mask mode the cleaned text is returned, in log
+    /// mode the original; in reject mode the first broken rule is returned as the error.
+    /// `field` names what is being checked, for the logs and error message.
+    pub fn apply<'a>(&self, field: &str, text: &'a str) -> Result<Cow<'a, str>, String> {
+        let Some(reason) = self.violation(text) else {
+            return Ok(Cow::Borrowed(text));
+        };
+
+        warn!(
+            "Filtered {} ({:?}): {}: '{}'",
+            field, self.mode, reason, text
+        );
+
+        match self.mode {
+            FilterMode::Mask => Ok(Cow::Owned(self.mask(text))),
+            FilterMode::Reject => Err(format!("Your {field} {reason}.")),
+            FilterMode::Log => Ok(Cow::Borrowed(text)),
+        }
+    }
+
+    fn violation(&self, text: &str) -> Option<&'static str> {
+        if self
+            .max_length
+            .is_some_and(|max| text.chars().count() > max)
+        {
+            return Some("is too long");
+        }
+
+        if self
+            .words
+            .as_ref()
+            .is_some_and(|words| words.is_match(text))
+        {
+            return Some("contains a banned word");
+        }
+
+        if self.rules.iter().any(|rule| rule.is_match(text)) {
+            return Some("is not allowed");
+        }
+
+        if self.max_repeat.is_some_and(|max| longest_run(text) > max) {
+            return Some("repeats a character too many times");
+        }
+
+        None
+    }
+
+    fn mask(&self, text: &str) -> String {
+        // Squash first so the masks themselves don't count as repeats
+        let mut masked = match self.max_repeat {
+            Some(max) => squash_runs(text, max),
+            None => text.to_string(),
+        };
+
+        for pattern in self.words.iter().chain(&self.rules) {
+            masked = pattern
+                .replace_all(&masked, |caps: &regex::Captures| {
+                    "*".repeat(caps[0].chars().count())
+                })
+                .into_owned();
+        }
+
+        if let Some(max) = self.max_length {
+            masked = masked.chars().take(max).collect();
+        }
+
+        masked
+    }
+}
+
+/// Length of the longest run of one repeated character.
+fn longest_run(text: &str) -> usize {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut last = None;
+
+    for c in text.chars() {
+        run = if Some(c) == last { run + 1 } else { 1 };
+        last = Some(c);
+        longest = longest.max(run);
+    }
+
+    longest
+}
+
+/// Shorten every run of one repeated character to at most `max`.
+fn squash_runs(text: &str, max: usize) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut run = 0;
+    let mut last = None;
+
+    for c in text.chars() {
+        run = if Some(c) == last { run + 1 } else { 1 };
+        last = Some(c);
+
+        if run <= max {
+            out.push(c);
+        }
+    }
+
+    out
+}
@@ -1,5 +1,6 @@
 mod change_room;
 mod character;
+mod chat;
 mod command;
 mod fight;
 mod leave;
@@ -7,3 +8,4 @@ mod loot;
 mod message;
 mod pvp_fight;
 mod start;
+mod tick;
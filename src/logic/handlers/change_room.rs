@@ -1,25 +1,24 @@
 use lurk_lcsc::{LurkError, PktChangeRoom, PktError, PktRoom};
-use lurk_lcsc::{send_error, send_room, send_to};
-use std::net::TcpStream;
-use std::sync::Arc;
 use tracing::{error, info};
 
-use crate::logic::GameState;
+use crate::logic::{Client, GameState};
 
 impl GameState {
-    pub fn handle_change_room(&mut self, author: Arc<TcpStream>, content: PktChangeRoom) {
+    pub fn handle_change_room(&mut self, author: Client, content: PktChangeRoom) {
         info!("Received: {}", content);
 
         // ================================================================================
         // Phase 1: Find player, validate, extract IDs
         // ================================================================================
         let (player_name, cur_room_id) = {
-            let Some((_, player)) = self.player_from_stream(&author) else {
+            let Some((_, player)) = self.player_from_client(&author) else {
                 error!("Unable to find player in map");
                 return;
             };
 
-            if !GameState::ensure_started(player, &author) {
+            if !GameState::ensure_started(player, &author)
+                || !GameState::ensure_alive(player, &author)
+            {
                 return;
             }
 
@@ -29,29 +28,23 @@ impl GameState {
         let nxt_room_id = content.room_number;
 
         if cur_room_id == nxt_room_id {
-            send_error!(
-                author.clone(),
-                PktError::new(LurkError::BADROOM, "Player is already in the room")
-            );
+            author.send_error(PktError::new(
+                LurkError::BADROOM,
+                "Player is already in the room",
+            ));
 
             return;
         }
 
         // Validate connection exists
         let Some(room) = self.rooms.get(&cur_room_id) else {
-            send_error!(
-                author.clone(),
-                PktError::new(LurkError::BADROOM, "Room not found!")
-            );
+            author.send_error(PktError::new(LurkError::BADROOM, "Room not found!"));
 
             return;
         };
 
         let Some(exit) = room.connections.get(&nxt_room_id) else {
-            send_error!(
-                author.clone(),
-                PktError::new(LurkError::BADROOM, "Invalid connection!")
-            );
+            author.send_error(PktError::new(LurkError::BADROOM, "Invalid connection!"));
 
             return;
         };
@@ -80,11 +73,11 @@ impl GameState {
         // Phase 3: Alert and send the updated data to the client
         // ================================================================================
         if let Some(new_room) = self.rooms.get(&nxt_room_id) {
-            send_room!(author.clone(), PktRoom::from(new_room));
+            author.send_room(PktRoom::from(new_room));
         }
 
         if let Some(player) = self.players.get(&player_name) {
-            let _ = send_to(author.as_ref(), player);
+            author.send_character(player);
         }
 
         // Alert old and new rooms about the player change
@@ -102,5 +95,10 @@ impl GameState {
         if let Some(new_room) = self.rooms.get(&nxt_room_id) {
             self.send_room_contents(&author, new_room);
         }
+
+        // ================================================================================
+        // Phase 4: Aggressive monsters don't wait to be attacked
+        // ================================================================================
+        self.ambush(&player_name, nxt_room_id);
     }
 }
@@ -1,15 +1,26 @@
 use lurk_lcsc::{CharacterFlags, LurkError, PktCharacter, PktError, PktType};
-use lurk_lcsc::{send_accept, send_error, send_to};
-use std::net::TcpStream;
 use std::sync::Arc;
 use tracing::info;
 
-use crate::logic::GameState;
+use crate::logic::audit::AuditEvent;
+use crate::logic::{Client, GameState};
 
 impl GameState {
-    pub fn handle_character(&mut self, author: Arc<TcpStream>, content: PktCharacter) {
+    pub fn handle_character(&mut self, author: Client, mut content: PktCharacter) {
         info!("Received: {}", content);
 
+        // A started player resending their character is spending stat points from leveling
+        if self.clients.get(&content.name) == Some(&author)
+            && self
+                .players
+                .get(&content.name)
+                .is_some_and(|player| player.flags.is_started())
+        {
+            self.allocate_stats(author, content);
+
+            return;
+        }
+
         // ================================================================================
         // Check the given stats are valid
         // ================================================================================
@@ -20,10 +31,34 @@ impl GameState {
             .unwrap_or(self.config.initial_points + 1); // This will cause the next check to fail
 
         if total_stats > self.config.initial_points {
-            send_error!(
-                author.clone(),
-                PktError::new(LurkError::STATERROR, "Invalid stats")
-            );
+            author.send_error(PktError::new(LurkError::STATERROR, "Invalid stats"));
+
+            return;
+        }
+
+        // ================================================================================
+        // Run the name and description through the content filter
+        // ================================================================================
+        let filter = &self.config.filter;
+
+        let (name, description) = match (
+            filter.apply("name", &content.name),
+            filter.apply("description", &content.description),
+        ) {
+            (Ok(name), Ok(description)) => (Arc::from(name.as_ref()), description.into()),
+            (Err(reason), _) | (_, Err(reason)) => {
+                author.send_error(PktError::new(LurkError::OTHER, &reason));
+
+                return;
+            }
+        };
+
+        content.name = name;
+        content.description = description;
+        content.description_len = content.description.len() as u16;
+
+        if let Err(reason) = self.validate_name(&content.name) {
+            author.send_error(PktError::new(LurkError::OTHER, &reason));
 
             return;
         }
@@ -33,6 +68,8 @@ impl GameState {
         // We ignore the flags from the client and set the correct ones accordingly.
         // Store the old room so that we may remove the player later and set ignore input room
         // ================================================================================
+        let new_player = !self.players.contains_key(&content.name);
+
         let (player_name, old_room_number) = {
             let player = match self.players.get_mut(&content.name) {
                 Some(player) => {
@@ -51,10 +88,10 @@ impl GameState {
             };
 
             if player.flags.is_started() {
-                send_error!(
-                    author.clone(),
-                    PktError::new(LurkError::PLAYEREXISTS, "Player is already in the game.")
-                );
+                author.send_error(PktError::new(
+                    LurkError::PLAYEREXISTS,
+                    "Player is already in the game.",
+                ));
 
                 return;
             }
@@ -62,19 +99,35 @@ impl GameState {
             let old_room_number = player.current_room;
 
             player.flags = CharacterFlags::alive();
-            player.author = Some(author.clone());
+
+            // Players who died and left before respawning come back restored
+            if player.health <= 0 {
+                player.health = self.config.respawn_health;
+                self.respawns.remove(&content.name);
+            }
+            player.author = None; // Connections live in `self.clients`, not on the packet
             player.current_room = 0; // Start in the first room
 
             (player.name.clone(), old_room_number)
         };
 
+        self.clients.insert(player_name.clone(), author.clone());
+
+        self.audit.record(AuditEvent::Character {
+            player: player_name.clone(),
+            new_player,
+            attack: content.attack,
+            defense: content.defense,
+            regen: content.regen,
+        });
+
         // ================================================================================
         // Send an Accept packet and updated character.
         // ================================================================================
-        send_accept!(author.clone(), PktType::CHARACTER);
+        author.send_accept(PktType::CHARACTER);
 
         if let Some(player) = self.players.get(&player_name) {
-            let _ = send_to(author.as_ref(), player);
+            author.send_character(player);
         }
 
         // ================================================================================
@@ -0,0 +1,225 @@
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::logic::{Channel, Client, GameState};
+
+const CHAT_HELP: &str = "Commands:
+/look             - Describe your surroundings
+/inventory        - List what you are carrying
+/who              - List the players in the game
+/stats            - Show your level, XP, stat points and status effects
+/say <message>    - Speak to everyone in the room
+/shout <message>  - Shout to everyone in the game
+/p <message>      - Talk to your party
+/party            - List your party
+/party invite <p> - Invite a player to your party
+/party accept     - Join the party you were invited to
+/party leave      - Leave your party
+/mute <channel>   - Stop hearing 'say', 'shout' or 'party'
+/unmute <channel> - Hear 'say', 'shout' or 'party' again
+/take <item>      - Pick up an item from the ground
+/drop <item>      - Drop an item on the ground
+/equip <item>     - Equip a weapon or armor
+/unequip <item>   - Take off a weapon or armor
+/use <item>       - Use a consumable
+/target <monster> - Choose which monster your next fight hits
+/respawn          - Return to life now instead of waiting
+/help             - Show this message";
+
+impl GameState {
+    /// Handle a command sent as a message to the narrator. Replies are narrated back to the
+    /// author, since the Lurk protocol has no packets for any of this.
+    pub fn handle_chat(&mut self, author: Client, command: &str) {
+        info!("Chat command: /{}", command);
+
+        let Some((name, player)) = self.player_from_client(&author) else {
+            error!("Unable to find player in map");
+            return;
+        };
+        let name = name.clone();
+
+        if !GameState::ensure_started(player, &author) {
+            return;
+        }
+
+        let alive = player.flags.is_alive();
+
+        let (verb, arg) = command
+            .split_once(char::is_whitespace)
+            .map(|(verb, arg)| (verb, arg.trim()))
+            .unwrap_or((command, ""));
+
+        match (verb.to_ascii_lowercase().as_str(), arg) {
+            ("help" | "", _) => self.narrate(&author, &name, CHAT_HELP),
+            ("look" | "l", _) => {
+                let description = self.describe_surroundings(&name);
+                self.narrate(&author, &name, &description);
+            }
+            ("inventory" | "inv" | "i", _) => {
+                let description = self.describe_inventory(&name);
+                self.narrate(&author, &name, &description);
+            }
+            ("stats", _) => {
+                let description = format!(
+                    "{} {}",
+                    self.describe_progress(&name),
+                    self.describe_effects(&name)
+                );
+                self.narrate(&author, &name, &description);
+            }
+            ("who", _) => {
+                let description = self.describe_players();
+                self.narrate(&author, &name, &description);
+            }
+            ("say" | "shout" | "p", "") => {
+                self.narrate(&author, &name, &format!("/{verb} what?"));
+            }
+            ("say", message) => {
+                let Some(room) = self
+                    .players
+                    .get(&name)
+                    .and_then(|player| self.rooms.get(&player.current_room))
+                else {
+                    error!("Player isn't in a valid room");
+                    return;
+                };
+
+                self.chat(Channel::Say, &name, room.players.iter(), message);
+            }
+            ("shout", message) => {
+                self.chat(Channel::Shout, &name, self.clients.keys(), message);
+            }
+            ("p", message) => self.party_chat(&author, &name, message),
+            ("party", arg) => self.handle_party(&author, &name, arg),
+            ("mute" | "unmute", arg) => {
+                let Ok(channel) = arg.parse::<Channel>() else {
+                    self.narrate(
+                        &author,
+                        &name,
+                        &format!("/{verb} 'say', 'shout' or 'party'?"),
+                    );
+                    return;
+                };
+
+                let muted = self.muted.entry(name.clone()).or_default();
+
+                if verb.eq_ignore_ascii_case("mute") {
+                    muted.insert(channel);
+                } else {
+                    muted.remove(&channel);
+                }
+
+                self.narrate(
+                    &author,
+                    &name,
+                    &format!("You {verb}d {}.", arg.to_ascii_lowercase()),
+                );
+            }
+            ("target", "") => {
+                self.narrate(&author, &name, "/target which monster?");
+            }
+            ("target", monster) => {
+                self.set_target(&author, &name, monster);
+            }
+            ("respawn", _) if !alive => self.respawn(&name),
+            ("respawn", _) => self.narrate(&author, &name, "You're not dead."),
+            ("take" | "drop" | "equip" | "unequip" | "use", _) if !alive => {
+                self.narrate(&author, &name, "The dead can't carry anything.");
+            }
+            ("take" | "drop" | "equip" | "unequip" | "use", "") => {
+                self.narrate(&author, &name, &format!("/{verb} which item?"));
+            }
+            ("take", item) => self.take_item(&author, &name, item),
+            ("drop", item) => self.drop_item(&author, &name, item),
+            ("equip", item) => self.equip_item(&author, &name, item),
+            ("unequip", item) => self.unequip_item(&author, &name, item),
+            ("use", item) => self.use_item(&author, &name, item),
+            _ => {
+                self.narrate(
+                    &author,
+                    &name,
+                    &format!("Unknown command '/{verb}'. Try /help."),
+                );
+            }
+        }
+    }
+
+    /// Describe the room a player is standing in: exits, who and what is there.
+    fn describe_surroundings(&self, name: &Arc<str>) -> String {
+        let Some(room) = self
+            .players
+            .get(name)
+            .and_then(|player| self.rooms.get(&player.current_room))
+        else {
+            return String::from("You are nowhere at all.");
+        };
+
+        let mut lines = vec![format!("{}: {}", room.title, room.desc)];
+
+        let mut exits: Vec<_> = room.connections.values().collect();
+        exits.sort_by_key(|conn| conn.room_number);
+
+        let exits: Vec<String> = exits
+            .iter()
+            .map(|conn| format!("{} ({})", conn.title, conn.room_number))
+            .collect();
+        lines.push(format!("Exits: {}", list_or_none(&exits)));
+
+        let others: Vec<String> = room
+            .players
+            .iter()
+            .filter(|other| *other != name)
+            .map(|other| other.to_string())
+            .collect();
+        lines.push(format!("Players: {}", list_or_none(&others)));
+
+        let monsters: Vec<String> = room
+            .monsters
+            .iter()
+            .flatten()
+            .map(|m| {
+                if m.health > 0 {
+                    format!("{} ({}/{} hp)", m.name, m.health, m.max_health)
+                } else {
+                    format!("{} (dead)", m.name)
+                }
+            })
+            .collect();
+        lines.push(format!("Monsters: {}", list_or_none(&monsters)));
+
+        let items: Vec<String> = room.items.iter().map(|item| item.to_string()).collect();
+        lines.push(format!("Items: {}", list_or_none(&items)));
+
+        lines.join("\n")
+    }
+
+    /// List every connected, started player and where they are.
+    fn describe_players(&self) -> String {
+        let mut online: Vec<String> = self
+            .clients
+            .keys()
+            .filter_map(|name| self.players.get(name))
+            .filter(|player| player.flags.is_started())
+            .map(|player| {
+                let room = self
+                    .rooms
+                    .get(&player.current_room)
+                    .map(|room| room.title.as_ref())
+                    .unwrap_or("somewhere");
+
+                format!("{} - {}", player.name, room)
+            })
+            .collect();
+        online.sort();
+
+        format!("{} player(s) online:\n{}", online.len(), online.join("\n"))
+    }
+}
+
+fn list_or_none(entries: &[String]) -> String {
+    if entries.is_empty() {
+        String::from("none")
+    } else {
+        entries.join(", ")
+    }
+}
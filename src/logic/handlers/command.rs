@@ -1,14 +1,21 @@
-use lurk_lcsc::send_message;
 use lurk_lcsc::{PktCharacter, PktMessage};
 use std::sync::Arc;
 use tracing::{error, info};
 
+use crate::logic::audit::AuditEvent;
+use crate::logic::death::corpse_name;
 use crate::logic::{Action, GameState};
 
 impl GameState {
     pub fn handle_command(&mut self, action: Action) {
         info!("Received: {}", action);
 
+        self.audit.record(AuditEvent::Command {
+            operator: action.operator.clone(),
+            kind: action.kind.clone(),
+            argv: action.argv.clone(),
+        });
+
         match action.kind.as_ref() {
             "help" => {
                 info!("{}", self.config.help_cmd);
@@ -32,26 +39,21 @@ impl GameState {
                 let name = action.argv[1].clone();
                 let content = action.argv[2..].join(" ");
 
-                let recipient = self
-                    .players
-                    .get(name.as_str())
-                    .and_then(|p| p.author.clone());
-
-                let Some(recipient) = recipient else {
+                let Some(recipient) = self.clients.get(name.as_str()) else {
                     error!("Player not found: {}", action.argv[1]);
                     return;
                 };
 
-                send_message!(recipient.clone(), PktMessage::server(&name, &content));
+                recipient.send_message(PktMessage::server(&name, &content));
             }
             "nuke" => {
                 info!("Nuke command received, removing disconnected players");
 
                 let to_remove: Vec<Arc<str>> = self
                     .players
-                    .iter()
-                    .filter(|(_, player)| player.author.is_none())
-                    .map(|(name, _)| name.clone())
+                    .keys()
+                    .filter(|name| !self.clients.contains_key(*name))
+                    .cloned()
                     .collect();
 
                 if to_remove.is_empty() {
@@ -59,10 +61,26 @@ impl GameState {
                     return;
                 }
 
-                // Remove from main list and room lists
+                // Remove from main list and room lists, along with their corpses
                 self.players.retain(|name, _| !to_remove.contains(name));
+                self.inventories.retain(|name, _| !to_remove.contains(name));
+                self.muted.retain(|name, _| !to_remove.contains(name));
+                self.progress.retain(|name, _| !to_remove.contains(name));
+                self.mailboxes.retain(|name, _| !to_remove.contains(name));
+                self.targets.retain(|name, _| !to_remove.contains(name));
+                self.effects.retain(|name, _| !to_remove.contains(name));
+                self.respawns.retain(|name, _| !to_remove.contains(name));
+                self.parties.retain(|name| !to_remove.contains(name));
+                self.loot_turns
+                    .retain(|group, _| !group.iter().any(|name| to_remove.contains(name)));
+
+                let corpses: Vec<String> = to_remove.iter().map(|name| corpse_name(name)).collect();
                 for room in self.rooms.values_mut() {
                     room.players.retain(|name| !to_remove.contains(name));
+
+                    if let Some(monsters) = &mut room.monsters {
+                        monsters.retain(|m| !corpses.iter().any(|c| c.as_str() == m.name.as_ref()));
+                    }
                 }
 
                 info!("Removed {} disconnected players", to_remove.len());
@@ -79,11 +97,16 @@ impl GameState {
 
                 for room in self.rooms.values_mut() {
                     if let Some(monsters) = &mut room.monsters {
+                        monsters.retain(|m| !(m.summoned && m.health <= 0)); // Bosses summon fresh minions
+
                         let pkts: Vec<PktCharacter> = monsters
                             .iter_mut()
                             .filter(|m| m.health <= 0 && m.max_health > 0)
                             .map(|m| {
                                 m.health = m.max_health;
+                                m.damage.clear();
+                                m.looters.clear();
+                                m.reset_phases();
                                 PktCharacter::from(m)
                             })
                             .collect();
@@ -109,6 +132,56 @@ impl GameState {
                 self.broadcast(String::from("All dead monsters have been revived!"));
                 info!("Revived {} monster(s)", revived_count);
             }
+            "inventory" => {
+                let Some(name) = action.argv.get(1) else {
+                    error!("Inventory command requires a player name");
+                    return;
+                };
+
+                let Some((name, _)) = self.players.get_key_value(name.as_str()) else {
+                    error!("Player not found: {}", name);
+                    return;
+                };
+
+                info!("{}", self.describe_inventory(name));
+            }
+            "mail" => {
+                let Some(name) = action.argv.get(1) else {
+                    let total: usize = self.mailboxes.values().map(|m| m.len()).sum();
+                    info!(
+                        "{} message(s) queued across {} mailbox(es)",
+                        total,
+                        self.mailboxes.len()
+                    );
+
+                    for (name, mailbox) in &self.mailboxes {
+                        info!("{}: {} message(s)", name, mailbox.len());
+                    }
+
+                    return;
+                };
+
+                let Some(mailbox) = self.mailboxes.get(name.as_str()) else {
+                    info!("No mail queued for {}", name);
+                    return;
+                };
+
+                for message in mailbox {
+                    info!("{} -> {}: {}", message.sender, name, message.message);
+                }
+            }
+            "items" => {
+                let mut items: Vec<_> = self.items.values().collect();
+                items.sort_by(|a, b| a.name.cmp(&b.name));
+
+                for item in items {
+                    info!("{}", item);
+                }
+
+                for room in self.rooms.values().filter(|room| !room.items.is_empty()) {
+                    info!("Room {} floor: {:?}", room.room_number, room.items);
+                }
+            }
             _ => {
                 error!("Unsupported command!");
             }
@@ -1,29 +1,45 @@
-use lurk_lcsc::send_error;
-use lurk_lcsc::{LurkError, PktCharacter, PktError, PktFight};
-use std::net::TcpStream;
+use lurk_lcsc::{CharacterFlags, LurkError, PktCharacter, PktError, PktFight};
+use rand::Rng;
 use std::sync::Arc;
 use tracing::{error, info};
 
-use crate::logic::GameState;
+use crate::logic::audit::AuditEvent;
+use crate::logic::combat::Blow;
+use crate::logic::effects::EffectKind;
+use crate::logic::{Client, GameState};
 
 impl GameState {
-    pub fn handle_fight(&mut self, author: Arc<TcpStream>, content: PktFight) {
+    pub fn handle_fight(&mut self, author: Client, content: PktFight) {
         info!("Received: {}", content);
 
         // Find the player and extract needed data in a scoped block
-        let (mut attacker, current_room) = {
-            let Some((_, player)) = self.player_from_stream(&author) else {
+        let (attacker, current_room) = {
+            let Some((_, player)) = self.player_from_client(&author) else {
                 error!("Unable to find player in map");
                 return;
             };
 
-            if !GameState::ensure_started(player, &author) {
+            if !GameState::ensure_started(player, &author)
+                || !GameState::ensure_alive(player, &author)
+            {
                 return;
             }
 
             (player.clone(), player.current_room)
         };
 
+        if self
+            .effects
+            .get(&attacker.name)
+            .is_some_and(|e| e.has(EffectKind::Stun))
+        {
+            author.send_error(PktError::new(
+                LurkError::OTHER,
+                "You are stunned and can't attack!",
+            ));
+            return;
+        }
+
         // ================================================================================
         // Collect all players that will join us in battle, then get the target monster,
         // check if they exists and are dead
@@ -36,38 +52,38 @@ impl GameState {
 
         room.players.retain(|player| player != &attacker.name); // Remove attacker for narration purposes
 
-        let in_battle: Vec<Arc<str>> = self
+        let mut in_battle: Vec<Arc<str>> = self
             .players
             .iter()
             .filter(|(_, p)| p.flags.is_battle() && p.current_room == current_room)
+            .filter(|(name, _)| {
+                !self.config.party_battles || self.parties.allied(&attacker.name, name)
+            })
             .map(|(name, _)| name.clone())
             .collect();
+        in_battle.sort(); // Sorted so a seeded RNG rolls for the same fighters in turn on replay
 
         // Find the target monster index so we can send messages
         // before acquiring mutable access for the fight.
         let target_idx = {
-            let Some(monsters) = self
+            if self
                 .rooms
                 .get(&current_room)
                 .and_then(|r| r.monsters.as_ref())
-            else {
-                send_error!(
-                    author.clone(),
-                    PktError::new(LurkError::NOFIGHT, "The room is eerily quiet...")
-                );
+                .is_none()
+            {
+                author.send_error(PktError::new(
+                    LurkError::NOFIGHT,
+                    "The room is eerily quiet...",
+                ));
                 return;
-            };
+            }
 
-            let Some((idx, _)) = monsters
-                .iter()
-                .enumerate()
-                .filter(|(_, m)| m.health > 0)
-                .min_by_key(|(_, m)| (m.health, m.name.clone()))
-            else {
-                send_error!(
-                    author.clone(),
-                    PktError::new(LurkError::NOFIGHT, "No monsters alive. Let them rest.")
-                );
+            let Some(idx) = self.fight_target(&attacker.name, current_room) else {
+                author.send_error(PktError::new(
+                    LurkError::NOFIGHT,
+                    "No monsters alive. Let them rest.",
+                ));
                 return;
             };
 
@@ -93,12 +109,20 @@ impl GameState {
         // ================================================================================
         // Calculate the fight logic: Action Phase!
         // ================================================================================
-        let battle_damage: u16 = in_battle
+        let strikers: Vec<(Arc<str>, u16)> = in_battle
             .iter()
+            .filter(|name| {
+                !self
+                    .effects
+                    .get(*name)
+                    .is_some_and(|e| e.has(EffectKind::Stun))
+            })
             .filter_map(|name| self.players.get(name))
-            .map(|p| p.attack)
-            .sum();
+            .map(|p| (p.name.clone(), p.attack))
+            .collect();
+        let battle_damage: u16 = strikers.iter().map(|(_, attack)| attack).sum();
         let mut victory = false;
+        let mut xp = 0;
 
         self.message_room(
             &room,
@@ -115,73 +139,194 @@ impl GameState {
             .as_mut()
             .expect("monsters confirmed present above")[target_idx];
 
-        let damage = battle_damage.saturating_sub(to_attack.defense);
-        let damage = damage.try_into().unwrap_or(i16::MAX); // We went out of bounds on damage, cap to i16 MAX int
+        let mut narration = Vec::new(); // Misses and crits, told to the room once the fight is over
+        let blow = self
+            .config
+            .combat
+            .roll(&mut self.rng, battle_damage, to_attack.defense);
+        let damage = blow.damage();
+
+        if !matches!(blow, Blow::Hit(_)) {
+            narration.push(blow.describe(&attacker.name, &to_attack.name));
+        }
 
         to_attack.health = to_attack.health.saturating_sub(damage);
 
         info!("'{}' dealt {} damage", attacker.name, damage);
 
+        // Credit each striker with their share of the blow, for dividing the loot later
+        for (name, attack) in &strikers {
+            let share = u32::try_from(damage).unwrap_or(0) * u32::from(*attack)
+                / u32::from(battle_damage.max(1));
+
+            *to_attack.damage.entry(name.clone()).or_default() += share;
+        }
+
+        // ================================================================================
+        // Bosses change as they weaken, and may call for help
+        // ================================================================================
+        let mut minions = Vec::new();
+
+        if to_attack.health > 0 {
+            for phase in to_attack.enter_phases() {
+                info!("'{}' entered a new phase", to_attack.name);
+
+                narration.push(phase.narration.into());
+                minions.extend(phase.minions);
+            }
+        }
+
+        let boss = to_attack.boss.is_some();
+
         if to_attack.health <= 0 {
             victory = true;
+            xp = to_attack.xp;
+            (to_attack.gold, to_attack.items) = to_attack.loot.roll(&mut self.rng);
 
             info!("'{}' defeated '{}'", attacker.name, to_attack.name);
+
+            self.audit.record(AuditEvent::Kill {
+                monster: to_attack.name.clone(),
+                room: current_room,
+                killer: attacker.name.clone(),
+                participants: in_battle.clone(),
+            });
         }
 
         // ================================================================================
-        // Calculate the fight logic: Defense Phase!
+        // Calculate the fight logic: Defense Phase! The monster strikes back at the party
         // ================================================================================
-        if !victory {
-            let damage = to_attack.attack.saturating_sub(attacker.defense);
-            let damage = damage.try_into().unwrap_or(i16::MAX); // We went out of bounds on damage, cap to i16 MAX int
+        let mut participants = in_battle;
 
-            attacker.health = attacker.health.saturating_sub(damage);
+        if !participants.contains(&attacker.name) {
+            participants.push(attacker.name.clone());
+            participants.sort();
+        }
 
-            info!(
-                "'{}' took {} damage from '{}'",
-                attacker.name, damage, to_attack.name
+        if victory {
+            let looters = self.config.loot_mode.looters(
+                &participants,
+                &attacker.name,
+                &to_attack.damage,
+                &mut self.loot_turns,
             );
+            to_attack.looters = looters;
+        }
+
+        // The monster is done changing; strike back with a copy so `self` is free again
+        let monster = to_attack.clone();
+        let mut dead = Vec::new();
+        let mut inflicted = Vec::new();
+
+        if !victory {
+            let victims =
+                self.config
+                    .retaliation
+                    .victims(&participants, &self.players, &mut self.rng);
+
+            for name in victims {
+                let (blow, damage) = self.monster_strike(&monster, &name);
+
+                if !matches!(blow, Blow::Hit(_)) {
+                    narration.push(blow.describe(&monster.name, &name));
+                }
+
+                // A blow that lands may carry one of the monster's effects with it
+                if blow != Blow::Miss {
+                    for effect in &monster.inflicts {
+                        if self.rng.random_bool(effect.chance.clamp(0.0, 1.0)) {
+                            inflicted.push((name.clone(), effect.clone()));
+                        }
+                    }
+                }
 
-            if attacker.health <= 0 {
-                info!("'{}' killed '{}'", to_attack.name, attacker.name);
+                info!("'{}' took {} damage from '{}'", name, damage, monster.name);
+
+                let Some(player) = self.players.get_mut(&name) else {
+                    continue;
+                };
+
+                if player.health <= 0 {
+                    info!("'{}' killed '{}'", monster.name, name);
+
+                    player.flags.remove(CharacterFlags::ALIVE); // No regen for the dead
+
+                    dead.push(name);
+                }
             }
         }
 
         // ================================================================================
         // Calculate the fight logic: End Phase!
         // ================================================================================
-        if attacker.flags.is_alive() {
-            let regen = attacker.regen.try_into().unwrap_or(i16::MAX);
+        for name in &participants {
+            let Some(player) = self.players.get_mut(name) else {
+                continue;
+            };
+
+            if player.flags.is_alive() {
+                let regen = player.regen.try_into().unwrap_or(i16::MAX);
 
-            info!("'{}' regenerated: {}", attacker.name, regen);
+                info!("'{}' regenerated: {}", name, regen);
 
-            attacker.health = attacker.health.saturating_add(regen); // We went out of bounds on regen, cap to i16 MAX int
+                player.health = player.health.saturating_add(regen); // We went out of bounds on regen, cap to i16 MAX int
+            }
         }
 
         // ================================================================================
-        // Update player HashMap with new stats and send all the updated players/ monster
-        // to client
+        // Send all the updated players and the monster to the room
         // ================================================================================
         info!("Updating players in fight");
 
-        let attacker_name = attacker.name.clone();
-        let _ = self.players.insert(attacker_name.clone(), attacker); // Move, not clone
-
-        for name in &in_battle {
-            if let Some(player) = self.players.get(name) {
-                let _ = self.players.insert(name.clone(), player.clone());
-            }
-        }
-
-        let monster_pkt: PktCharacter = to_attack.into();
-        let to_update = in_battle.iter().filter_map(|name| self.players.get(name));
+        let attacker_name = attacker.name;
+        let monster_pkt = PktCharacter::from(&monster);
+        let to_update = participants
+            .iter()
+            .filter_map(|name| self.players.get(name));
 
-        room.players.insert(attacker_name); // Add the name back so the attacker gets updated
+        room.players.insert(attacker_name.clone()); // Add the name back so the attacker gets updated
 
         for player in to_update {
             self.alert_room(&room, player);
         }
 
         self.alert_room(&room, &monster_pkt);
+
+        for line in narration {
+            self.message_room(&room, line, true);
+        }
+
+        for minion in self.spawn_minions(current_room, &minions) {
+            self.message_room(&room, format!("{} joins the fight!", minion.name), true);
+            self.alert_room(&room, &minion);
+        }
+
+        if victory && boss {
+            let mut victors: Vec<&str> = participants.iter().map(|name| name.as_ref()).collect();
+            victors.sort();
+
+            self.broadcast(format!(
+                "{} has been defeated by {}!",
+                monster_pkt.name,
+                victors.join(", ")
+            ));
+        }
+
+        for (name, effect) in &inflicted {
+            if !dead.contains(name) {
+                self.apply_effect(name, effect);
+            }
+        }
+
+        for name in &dead {
+            self.kill_player(name, &monster_pkt.name);
+        }
+
+        // ================================================================================
+        // Share the monster's experience between everyone who fought it
+        // ================================================================================
+        if victory {
+            self.award_xp(&participants, &attacker_name, xp);
+        }
     }
 }
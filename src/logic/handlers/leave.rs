@@ -1,28 +1,41 @@
 use lurk_lcsc::{CharacterFlags, PktLeave};
-use std::net::TcpStream;
-use std::sync::Arc;
 use tracing::{error, info, warn};
 
-use crate::logic::GameState;
+use crate::logic::audit::AuditEvent;
+use crate::logic::{Client, GameState};
 
 impl GameState {
-    pub fn handle_leave(&mut self, author: Arc<TcpStream>, content: PktLeave) {
+    pub fn handle_leave(&mut self, author: Client, content: PktLeave) {
         info!("Received: {}", content);
 
         // ================================================================================
         // Grab the player and deactivate them, extract name for later lookups
         // ================================================================================
         let (player_name, current_room) = {
-            let Some((_, player)) = self.player_from_stream(&author) else {
+            let Some((_, player)) = self.player_from_client(&author) else {
                 return;
             };
 
             player.flags = CharacterFlags::empty();
-            player.author = None;
 
             (player.name.clone(), player.current_room)
         };
 
+        self.clients.remove(&player_name);
+
+        if let Some(remaining) = self.parties.leave(&player_name) {
+            for member in &remaining {
+                if let Some(client) = self.clients.get(member) {
+                    self.narrate(client, member, &format!("{player_name} left the party."));
+                }
+            }
+        }
+
+        self.audit.record(AuditEvent::Leave {
+            player: player_name.clone(),
+            room: current_room,
+        });
+
         // ================================================================================
         // Alert the server and the room
         // ================================================================================
@@ -37,7 +50,7 @@ impl GameState {
             self.alert_room(room, player);
         }
 
-        match author.shutdown(std::net::Shutdown::Both) {
+        match author.shutdown() {
             Ok(_) => info!("Connection shutdown successfully"),
             Err(e) => error!("Failed to shutdown connection: {}", e),
         }
@@ -1,24 +1,26 @@
 use lurk_lcsc::{LurkError, PktCharacter, PktError, PktLoot};
-use lurk_lcsc::{send_error, send_to};
-use std::net::TcpStream;
 use std::sync::Arc;
 use tracing::{error, info};
 
-use crate::logic::GameState;
+use crate::logic::audit::AuditEvent;
+use crate::logic::distribution::{LootMode, split_evenly, split_gold};
+use crate::logic::{Client, GameState, TargetPacket};
 
 impl GameState {
-    pub fn handle_loot(&mut self, author: Arc<TcpStream>, content: PktLoot) {
+    pub fn handle_loot(&mut self, author: Client, content: PktLoot) {
         info!("Received: {}", content);
 
         // Find the player, validate, and extract needed data
         let (player_name, current_room) = {
-            let Some((name, player)) = self.player_from_stream(&author) else {
+            let Some((name, player)) = self.player_from_client(&author) else {
                 error!("Unable to find player in map");
                 return;
             };
             info!("Found player '{}'", name);
 
-            if !GameState::ensure_started(player, &author) {
+            if !GameState::ensure_started(player, &author)
+                || !GameState::ensure_alive(player, &author)
+            {
                 return;
             }
 
@@ -35,10 +37,7 @@ impl GameState {
         };
 
         let Some(monsters) = &mut room.monsters else {
-            send_error!(
-                author.clone(),
-                PktError::new(LurkError::OTHER, "No monsters to loot!")
-            );
+            author.send_error(PktError::new(LurkError::OTHER, "No monsters to loot!"));
 
             return;
         };
@@ -47,28 +46,49 @@ impl GameState {
             .iter_mut()
             .find(|m| m.name.as_ref() == content.target_name.as_ref())
         else {
-            send_error!(
-                author.clone(),
-                PktError::new(LurkError::BADMONSTER, "Monster doesn't exist!")
-            );
+            author.send_error(PktError::new(
+                LurkError::BADMONSTER,
+                "Monster doesn't exist!",
+            ));
 
             return;
         };
 
+        if to_loot.health > 0 && self.config.target_packet == TargetPacket::Loot {
+            self.set_target(&author, &player_name, &content.target_name);
+
+            return;
+        }
+
         if to_loot.health > 0 {
-            send_error!(
-                author.clone(),
-                PktError::new(LurkError::BADMONSTER, "Monster is still alive!")
-            );
+            author.send_error(PktError::new(
+                LurkError::BADMONSTER,
+                "Monster is still alive!",
+            ));
 
             return;
         }
 
-        if to_loot.gold == 0 {
-            send_error!(
-                author.clone(),
-                PktError::new(LurkError::BADMONSTER, "Monster already looted!")
-            );
+        if to_loot.gold == 0 && to_loot.items.is_empty() {
+            author.send_error(PktError::new(
+                LurkError::BADMONSTER,
+                "Monster already looted!",
+            ));
+
+            return;
+        }
+
+        // Party members share each other's right to loot
+        if !to_loot.looters.is_empty()
+            && !to_loot
+                .looters
+                .iter()
+                .any(|looter| self.parties.allied(looter, &player_name))
+        {
+            author.send_error(PktError::new(
+                LurkError::BADMONSTER,
+                "You didn't earn this loot!",
+            ));
 
             return;
         }
@@ -76,20 +96,75 @@ impl GameState {
         // Shuffle gold to player
         let gold = to_loot.gold;
         to_loot.gold = 0;
+        let items = std::mem::take(&mut to_loot.items);
+
+        let shares = match self.config.loot_mode {
+            LootMode::SplitByDamage => split_gold(gold, &player_name, &to_loot.damage),
+            _ => {
+                let present: Vec<Arc<str>> = self
+                    .parties
+                    .members(&player_name)
+                    .into_iter()
+                    .filter(|member| {
+                        self.players
+                            .get(member)
+                            .is_some_and(|p| p.current_room == current_room)
+                    })
+                    .collect();
+
+                split_evenly(gold, &player_name, &present)
+            }
+        };
 
         let monster_pkt = PktCharacter::from(to_loot);
 
-        // Re-lookup player to transfer gold
-        let Some(player) = self.players.get_mut(&player_name) else {
+        self.audit.record(AuditEvent::Loot {
+            player: player_name.clone(),
+            monster: monster_pkt.name.clone(),
+            room: current_room,
+            gold,
+            items: items.clone(),
+        });
+
+        // ================================================================================
+        // Hand everyone their share of the gold; fellow fighters hear about theirs from
+        // the narrator
+        // ================================================================================
+        for (name, share) in shares {
+            let Some(player) = self.players.get_mut(&name) else {
+                error!("Player '{}' disappeared during loot", name);
+                continue;
+            };
+            player.gold = player.gold.saturating_add(share);
+
+            if name == player_name {
+                continue;
+            }
+
+            if let Some(client) = self.clients.get(&name).cloned() {
+                self.narrate(
+                    &client,
+                    &name,
+                    &format!(
+                        "{player_name} looted {}; your share is {share} gold.",
+                        monster_pkt.name
+                    ),
+                );
+                self.send_player_update(&client, &name);
+            }
+        }
+
+        // ================================================================================
+        // Send updated player and monster back to author, then hand over any items
+        // ================================================================================
+        let Some(player) = self.players.get(&player_name) else {
             error!("Player disappeared during loot");
             return;
         };
-        player.gold += gold;
 
-        // ================================================================================
-        // Send updated player and monster back to author
-        // ================================================================================
-        let _ = send_to(author.as_ref(), player);
-        let _ = send_to(author.as_ref(), &monster_pkt);
+        author.send_character(player);
+        author.send_character(&monster_pkt);
+
+        self.give_items(&author, &player_name, items);
     }
 }
@@ -1,39 +1,70 @@
 use lurk_lcsc::{LurkError, PktError, PktMessage};
-use lurk_lcsc::{send_error, send_message};
-use std::{net::TcpStream, sync::Arc};
 use tracing::info;
 
-use crate::logic::GameState;
+use crate::logic::{Client, GameState};
 
 impl GameState {
-    pub fn handle_message(&self, author: Arc<TcpStream>, content: PktMessage) {
+    pub fn handle_message(&mut self, author: Client, mut content: PktMessage) {
         info!("Received: {}", content);
 
+        match self.config.filter.apply("message", &content.message) {
+            Ok(message) => {
+                content.message = message.as_ref().into();
+                content.message_len = content.message.len() as u16;
+            }
+            Err(reason) => {
+                author.send_error(PktError::new(LurkError::OTHER, &reason));
+
+                return;
+            }
+        }
+
+        // Lurk has no packets for things like items, so players message the narrator instead
+        if content
+            .recipient
+            .eq_ignore_ascii_case(&self.config.narrator_name)
+        {
+            let command = content.message.trim();
+            let command = command.strip_prefix('/').unwrap_or(command);
+
+            self.handle_chat(author, command);
+
+            return;
+        }
+
         // ================================================================================
         // Get the recipient player and their connection fd to send them the message.
+        // Known players who are offline get it in their mailbox instead.
         // ================================================================================
-        let Some(player) = self.players.get(content.recipient.as_ref()) else {
-            send_error!(
-                author.clone(),
-                PktError::new(LurkError::OTHER, "Player not found")
-            );
+        let Some((name, player)) = self.players.get_key_value(content.recipient.as_ref()) else {
+            author.send_error(PktError::new(LurkError::OTHER, "Player not found"));
 
             return;
         };
 
-        if !GameState::ensure_started(player, &author) {
-            return;
-        }
+        let Some(recipient) = self.clients.get(name) else {
+            let recipient = name.clone();
+
+            // Only started players may leave mail, and always under their own name
+            let Some((sender, player)) = self.player_from_client(&author) else {
+                author.send_error(PktError::new(LurkError::NOTREADY, "Start the game first!"));
+                return;
+            };
+
+            if !GameState::ensure_started(player, &author) {
+                return;
+            }
 
-        let Some(author) = &player.author else {
-            send_error!(
-                author.clone(),
-                PktError::new(LurkError::OTHER, "Not connected")
-            );
+            content.sender = sender.as_ref().into();
+            self.queue_mail(&author, recipient, content);
 
             return;
         };
 
-        send_message!(author.clone(), content);
+        if !GameState::ensure_started(player, &author) {
+            return;
+        }
+
+        recipient.send_message(content);
     }
 }
@@ -1,18 +1,48 @@
-use lurk_lcsc::send_error;
 use lurk_lcsc::{LurkError, PktError, PktPVPFight};
-use std::net::TcpStream;
-use std::sync::Arc;
-use tracing::info;
+use tracing::{error, info};
 
-use crate::logic::GameState;
+use crate::logic::{Client, GameState, TargetPacket};
 
 impl GameState {
-    pub fn handle_pvp_fight(&self, author: Arc<TcpStream>, content: PktPVPFight) {
+    pub fn handle_pvp_fight(&mut self, author: Client, content: PktPVPFight) {
         info!("Received: {}", content);
 
-        send_error!(
-            author.clone(),
-            PktError::new(LurkError::NOPLAYERCOMBAT, "No player combat allowed")
-        );
+        // ================================================================================
+        // Players can't fight each other, but the packet may be used to pick a monster
+        // ================================================================================
+        if self.config.target_packet == TargetPacket::PvpFight {
+            let Some((name, player)) = self.player_from_client(&author) else {
+                error!("Unable to find player in map");
+                return;
+            };
+
+            if !GameState::ensure_started(player, &author)
+                || !GameState::ensure_alive(player, &author)
+            {
+                return;
+            }
+
+            let (name, current_room) = (name.clone(), player.current_room);
+
+            let is_monster = self
+                .rooms
+                .get(&current_room)
+                .and_then(|room| room.monsters.as_ref())
+                .is_some_and(|monsters| {
+                    monsters
+                        .iter()
+                        .any(|m| m.name.eq_ignore_ascii_case(&content.target_name))
+                });
+
+            if is_monster {
+                self.set_target(&author, &name, &content.target_name);
+                return;
+            }
+        }
+
+        author.send_error(PktError::new(
+            LurkError::NOPLAYERCOMBAT,
+            "No player combat allowed",
+        ));
     }
 }
@@ -1,30 +1,28 @@
 use lurk_lcsc::{CharacterFlags, LurkError, PktError, PktRoom, PktStart};
-use lurk_lcsc::{send_error, send_room, send_to};
-use std::net::TcpStream;
-use std::sync::Arc;
 use tracing::{error, info};
 
-use crate::logic::GameState;
+use crate::logic::audit::AuditEvent;
+use crate::logic::{Client, GameState};
 
 impl GameState {
-    pub fn handle_start(&mut self, author: Arc<TcpStream>, content: PktStart) {
+    pub fn handle_start(&mut self, author: Client, content: PktStart) {
         info!("Received: {}", content);
 
         // ================================================================================
         // Phase 1: Find player, validate, activate, extract name
         // ================================================================================
         let player_name = {
-            let Some((name, player)) = self.player_from_stream(&author) else {
+            let Some((name, player)) = self.player_from_client(&author) else {
                 error!("Unable to find player in map");
                 return;
             };
             info!("Found player '{}'", name);
 
             if !player.flags.is_ready() {
-                send_error!(
-                    author.clone(),
-                    PktError::new(LurkError::NOTREADY, "Supply of valid player first!")
-                );
+                author.send_error(PktError::new(
+                    LurkError::NOTREADY,
+                    "Supply of valid player first!",
+                ));
 
                 return;
             }
@@ -34,9 +32,14 @@ impl GameState {
             player.name.clone()
         };
 
+        self.audit.record(AuditEvent::Start {
+            player: player_name.clone(),
+            room: 0,
+        });
+
         // Send updated character
         if let Some(player) = self.players.get(&player_name) {
-            let _ = send_to(author.as_ref(), player);
+            author.send_character(player);
         }
 
         // ================================================================================
@@ -54,14 +57,14 @@ impl GameState {
         // ================================================================================
         if let Some(room) = self.rooms.get_mut(&0) {
             info!("Adding player to starting room");
-            room.players.insert(player_name);
+            room.players.insert(player_name.clone());
         }
 
         // ================================================================================
         // Send room, connections, and contents (shared borrows)
         // ================================================================================
         if let Some(room) = self.rooms.get(&0) {
-            send_room!(author.clone(), PktRoom::from(room));
+            author.send_room(PktRoom::from(room));
         }
 
         self.send_connections(&author, 0);
@@ -69,5 +72,7 @@ impl GameState {
         if let Some(room) = self.rooms.get(&0) {
             self.send_room_contents(&author, room);
         }
+
+        self.deliver_mail(&author, &player_name);
     }
 }
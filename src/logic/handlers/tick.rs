@@ -0,0 +1,30 @@
+use std::sync::Arc;
+use tracing::trace;
+
+use crate::logic::GameState;
+
+impl GameState {
+    pub fn handle_tick(&mut self) {
+        self.tick += 1;
+
+        trace!("Tick {}", self.tick);
+
+        self.tick_effects();
+        self.wander_monsters();
+
+        // ================================================================================
+        // Respawn connected players whose time is up; offline players are revived when
+        // they next send their character
+        // ================================================================================
+        let due: Vec<Arc<str>> = self
+            .respawns
+            .iter()
+            .filter(|(name, at)| **at <= self.tick && self.clients.contains_key(*name))
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        for name in due {
+            self.respawn(&name);
+        }
+    }
+}
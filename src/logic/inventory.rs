@@ -0,0 +1,316 @@
+use lurk_lcsc::{PktCharacter, PktMessage};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::logic::map::{Item, ItemKind};
+use crate::logic::{Client, GameState};
+
+/// Items carried by a player. Kept next to the player's `PktCharacter`, keyed by name,
+/// since the Lurk character packet has nowhere to put them.
+#[derive(Serialize, Default, Clone)]
+pub struct Inventory {
+    pub items: Vec<Arc<str>>,
+    pub weapon: Option<Arc<str>>,
+    pub armor: Option<Arc<str>>,
+}
+
+impl Inventory {
+    /// Find a carried item by name, ignoring case.
+    pub fn find(&self, name: &str) -> Option<&Arc<str>> {
+        self.items
+            .iter()
+            .find(|item| item.eq_ignore_ascii_case(name))
+    }
+
+    fn remove(&mut self, name: &Arc<str>) {
+        if let Some(idx) = self.items.iter().position(|item| item == name) {
+            self.items.remove(idx);
+        }
+    }
+
+    fn is_equipped(&self, name: &Arc<str>) -> bool {
+        self.weapon.as_ref() == Some(name) || self.armor.as_ref() == Some(name)
+    }
+}
+
+fn apply(player: &mut PktCharacter, item: &Item) {
+    player.attack = player.attack.saturating_add(item.attack);
+    player.defense = player.defense.saturating_add(item.defense);
+    player.regen = player.regen.saturating_add(item.regen);
+}
+
+fn unapply(player: &mut PktCharacter, item: &Item) {
+    player.attack = player.attack.saturating_sub(item.attack);
+    player.defense = player.defense.saturating_sub(item.defense);
+    player.regen = player.regen.saturating_sub(item.regen);
+}
+
+impl GameState {
+    /// Send a narrator message to a single player.
+    pub fn narrate(&self, author: &Client, name: &str, message: &str) {
+        author.send_message(PktMessage::narrator(name, message));
+    }
+
+    /// Describe what a player is carrying and wearing.
+    pub fn describe_inventory(&self, name: &Arc<str>) -> String {
+        let Some(inventory) = self.inventories.get(name) else {
+            return String::from("You are carrying nothing.");
+        };
+
+        if inventory.items.is_empty() {
+            return String::from("You are carrying nothing.");
+        }
+
+        let lines: Vec<String> = inventory
+            .items
+            .iter()
+            .map(|item| {
+                let equipped = if inventory.is_equipped(item) {
+                    " (equipped)"
+                } else {
+                    ""
+                };
+
+                match self.items.get(item) {
+                    Some(def) => format!("- {def}{equipped}"),
+                    None => format!("- {item}{equipped}"),
+                }
+            })
+            .collect();
+
+        format!("You are carrying:\n{}", lines.join("\n"))
+    }
+
+    /// Give items to a player, narrating each one.
+    pub fn give_items(&mut self, author: &Client, name: &Arc<str>, items: Vec<Arc<str>>) {
+        for item in &items {
+            self.narrate(author, name, &format!("You obtained {item}."));
+        }
+
+        self.inventories
+            .entry(name.clone())
+            .or_default()
+            .items
+            .extend(items);
+    }
+
+    /// Pick up an item from the floor of the player's room.
+    pub fn take_item(&mut self, author: &Client, name: &Arc<str>, target: &str) {
+        let Some(current_room) = self.players.get(name).map(|p| p.current_room) else {
+            error!("Unable to find player '{}'", name);
+            return;
+        };
+
+        let Some(room) = self.rooms.get_mut(&current_room) else {
+            error!("Player isn't in a valid room");
+            return;
+        };
+
+        let Some(idx) = room
+            .items
+            .iter()
+            .position(|item| item.eq_ignore_ascii_case(target))
+        else {
+            self.narrate(author, name, &format!("There is no {target} here."));
+            return;
+        };
+
+        let item = room.items.remove(idx);
+
+        info!("'{}' picked up '{}'", name, item);
+
+        if let Some(room) = self.rooms.get(&current_room) {
+            self.message_room(room, format!("{name} picked up {item}."), true);
+        }
+
+        self.give_items(author, name, vec![item]);
+    }
+
+    /// Drop a carried item on the floor of the player's room, unequipping it first.
+    pub fn drop_item(&mut self, author: &Client, name: &Arc<str>, target: &str) {
+        let Some(item) = self
+            .inventories
+            .get(name)
+            .and_then(|inv| inv.find(target))
+            .cloned()
+        else {
+            self.narrate(author, name, &format!("You don't have {target}."));
+            return;
+        };
+
+        self.unequip(name, &item);
+
+        if let Some(inventory) = self.inventories.get_mut(name) {
+            inventory.remove(&item);
+        }
+
+        let Some(current_room) = self.players.get(name).map(|p| p.current_room) else {
+            error!("Unable to find player '{}'", name);
+            return;
+        };
+
+        if let Some(room) = self.rooms.get_mut(&current_room) {
+            room.items.push(item.clone());
+        }
+
+        info!("'{}' dropped '{}'", name, item);
+
+        if let Some(room) = self.rooms.get(&current_room) {
+            self.message_room(room, format!("{name} dropped {item}."), true);
+        }
+
+        self.send_player_update(author, name);
+    }
+
+    /// Equip a carried weapon or armor, swapping out whatever was in that slot.
+    pub fn equip_item(&mut self, author: &Client, name: &Arc<str>, target: &str) {
+        let Some(item) = self
+            .inventories
+            .get(name)
+            .and_then(|inv| inv.find(target))
+            .cloned()
+        else {
+            self.narrate(author, name, &format!("You don't have {target}."));
+            return;
+        };
+
+        let Some(def) = self.items.get(&item).cloned() else {
+            error!("Item '{}' has no definition", item);
+            return;
+        };
+
+        let Some(inventory) = self.inventories.get(name) else {
+            return;
+        };
+
+        let previous = match def.kind {
+            ItemKind::Weapon => inventory.weapon.clone(),
+            ItemKind::Armor => inventory.armor.clone(),
+            ItemKind::Consumable => {
+                self.narrate(author, name, &format!("{item} can't be equipped."));
+                return;
+            }
+        };
+
+        if previous.as_ref() == Some(&item) {
+            self.narrate(author, name, &format!("{item} is already equipped."));
+            return;
+        }
+
+        if let Some(previous) = previous {
+            self.unequip(name, &previous);
+        }
+
+        if let Some(inventory) = self.inventories.get_mut(name) {
+            match def.kind {
+                ItemKind::Weapon => inventory.weapon = Some(item.clone()),
+                ItemKind::Armor => inventory.armor = Some(item.clone()),
+                ItemKind::Consumable => unreachable!("consumables are rejected above"),
+            }
+        }
+
+        if let Some(player) = self.players.get_mut(name) {
+            apply(player, &def);
+        }
+
+        info!("'{}' equipped '{}'", name, item);
+
+        self.narrate(author, name, &format!("You equipped {item}."));
+        self.send_player_update(author, name);
+    }
+
+    /// Take off an equipped item.
+    pub fn unequip_item(&mut self, author: &Client, name: &Arc<str>, target: &str) {
+        let Some(item) = self
+            .inventories
+            .get(name)
+            .and_then(|inv| inv.find(target).filter(|item| inv.is_equipped(item)))
+            .cloned()
+        else {
+            self.narrate(author, name, &format!("You don't have {target} equipped."));
+            return;
+        };
+
+        self.unequip(name, &item);
+
+        self.narrate(author, name, &format!("You unequipped {item}."));
+        self.send_player_update(author, name);
+    }
+
+    /// Consume a carried consumable, applying its modifiers permanently.
+    pub fn use_item(&mut self, author: &Client, name: &Arc<str>, target: &str) {
+        let Some(item) = self
+            .inventories
+            .get(name)
+            .and_then(|inv| inv.find(target))
+            .cloned()
+        else {
+            self.narrate(author, name, &format!("You don't have {target}."));
+            return;
+        };
+
+        let Some(def) = self.items.get(&item).cloned() else {
+            error!("Item '{}' has no definition", item);
+            return;
+        };
+
+        if def.kind != ItemKind::Consumable {
+            self.narrate(
+                author,
+                name,
+                &format!("{item} can't be used; try equipping it."),
+            );
+            return;
+        }
+
+        if let Some(inventory) = self.inventories.get_mut(name) {
+            inventory.remove(&item);
+        }
+
+        if let Some(player) = self.players.get_mut(name) {
+            apply(player, &def);
+            player.health = player.health.saturating_add(def.health);
+        }
+
+        info!("'{}' used '{}'", name, item);
+
+        self.narrate(author, name, &format!("You used {item}."));
+
+        for effect in &def.effects {
+            self.apply_effect(name, effect);
+        }
+        self.send_player_update(author, name);
+    }
+
+    /// Clear an equipment slot holding `item` and remove its modifiers from the player.
+    fn unequip(&mut self, name: &Arc<str>, item: &Arc<str>) {
+        let Some(inventory) = self.inventories.get_mut(name) else {
+            return;
+        };
+
+        if inventory.weapon.as_ref() == Some(item) {
+            inventory.weapon = None;
+        } else if inventory.armor.as_ref() == Some(item) {
+            inventory.armor = None;
+        } else {
+            return;
+        }
+
+        if let (Some(player), Some(def)) = (self.players.get_mut(name), self.items.get(item)) {
+            unapply(player, def);
+        }
+    }
+
+    /// Send the player's updated character to them and to their room.
+    pub fn send_player_update(&self, author: &Client, name: &Arc<str>) {
+        let Some(player) = self.players.get(name) else {
+            return;
+        };
+
+        match self.rooms.get(&player.current_room) {
+            Some(room) if room.players.contains(name) => self.alert_room(room, player),
+            _ => author.send_character(player),
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use lurk_lcsc::{LurkError, PktCharacter, PktError, PktType};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::logic::audit::AuditEvent;
+use crate::logic::{Client, GameState};
+
+/// A player's experience, kept next to their `PktCharacter` since Lurk has no XP field.
+#[derive(Serialize, Clone, Debug)]
+pub struct Progress {
+    pub level: u16,
+    pub xp: u32,
+    /// Stat points earned from leveling that haven't been allocated yet
+    pub unspent: u16,
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self {
+            level: 1,
+            xp: 0,
+            unspent: 0,
+        }
+    }
+}
+
+impl GameState {
+    /// XP needed to reach the level after `level`, if there is one.
+    pub fn next_level_xp(&self, level: u16) -> Option<u32> {
+        self.config
+            .xp_thresholds
+            .get(level.saturating_sub(1) as usize)
+            .copied()
+    }
+
+    /// Split a monster's XP between everyone who fought it; the killer gets any remainder.
+    pub fn award_xp(&mut self, participants: &[Arc<str>], killer: &Arc<str>, xp: u32) {
+        if xp == 0 || participants.is_empty() {
+            return;
+        }
+
+        let share = xp / participants.len() as u32;
+        let remainder = xp % participants.len() as u32;
+
+        for name in participants {
+            let gained = if name == killer {
+                share + remainder
+            } else {
+                share
+            };
+
+            self.grant_xp(name, gained);
+        }
+    }
+
+    /// Add XP to a player, leveling them up as many times as it allows.
+    pub fn grant_xp(&mut self, name: &Arc<str>, gained: u32) {
+        if gained == 0 {
+            return;
+        }
+
+        let points_per_level = self.config.points_per_level;
+        let mut progress = self.progress.get(name).cloned().unwrap_or_default();
+        let mut levels = Vec::new();
+
+        progress.xp = progress.xp.saturating_add(gained);
+
+        while let Some(needed) = self.next_level_xp(progress.level)
+            && progress.xp >= needed
+        {
+            progress.level += 1;
+            progress.unspent = progress.unspent.saturating_add(points_per_level);
+            levels.push(progress.level);
+        }
+
+        info!("'{}' gained {} XP ({} total)", name, gained, progress.xp);
+
+        let unspent = progress.unspent;
+        self.progress.insert(name.clone(), progress);
+
+        let Some(author) = self.clients.get(name).cloned() else {
+            return;
+        };
+
+        self.narrate(&author, name, &format!("You gained {gained} XP."));
+
+        for level in levels {
+            info!("'{}' reached level {}", name, level);
+
+            self.audit.record(AuditEvent::LevelUp {
+                player: name.clone(),
+                level,
+            });
+            self.narrate(
+                &author,
+                name,
+                &format!(
+                    "You reached level {level}! You have {unspent} stat point(s) to spend; \
+                     resend your character with higher stats to allocate them."
+                ),
+            );
+        }
+    }
+
+    /// Describe a player's level, XP and unspent points.
+    pub fn describe_progress(&self, name: &Arc<str>) -> String {
+        let progress = self.progress.get(name).cloned().unwrap_or_default();
+
+        let next = match self.next_level_xp(progress.level) {
+            Some(needed) => format!(
+                "{}/{} XP to level {}",
+                progress.xp,
+                needed,
+                progress.level + 1
+            ),
+            None => format!("{} XP, max level", progress.xp),
+        };
+
+        format!(
+            "Level {}: {}. {} stat point(s) to spend.",
+            progress.level, next, progress.unspent
+        )
+    }
+
+    /// Spend a started player's unspent stat points. `content` carries the stats they want;
+    /// no stat may go down and the increase can't exceed what they have unspent.
+    pub fn allocate_stats(&mut self, author: Client, content: PktCharacter) {
+        let Some(player) = self.players.get(&content.name) else {
+            return;
+        };
+
+        let unspent = self
+            .progress
+            .get(&content.name)
+            .map(|p| p.unspent)
+            .unwrap_or_default();
+
+        let deltas = [
+            content.attack.checked_sub(player.attack),
+            content.defense.checked_sub(player.defense),
+            content.regen.checked_sub(player.regen),
+        ];
+
+        let Some(spent) = deltas
+            .iter()
+            .try_fold(0u16, |sum, delta| sum.checked_add((*delta)?))
+        else {
+            author.send_error(PktError::new(
+                LurkError::STATERROR,
+                "Stats can't be lowered once in the game.",
+            ));
+
+            return;
+        };
+
+        if spent > unspent {
+            author.send_error(PktError::new(
+                LurkError::STATERROR,
+                &format!("You only have {unspent} stat point(s) to spend."),
+            ));
+
+            return;
+        }
+
+        let total = content
+            .attack
+            .checked_add(content.defense)
+            .and_then(|sum| sum.checked_add(content.regen));
+
+        if total.is_none_or(|total| total > self.config.stat_limit) {
+            author.send_error(PktError::new(
+                LurkError::STATERROR,
+                &format!("Stats can't total more than {}.", self.config.stat_limit),
+            ));
+
+            return;
+        }
+
+        if let Some(progress) = self.progress.get_mut(&content.name) {
+            progress.unspent -= spent;
+        }
+
+        let Some(player) = self.players.get_mut(&content.name) else {
+            return;
+        };
+
+        player.attack = content.attack;
+        player.defense = content.defense;
+        player.regen = content.regen;
+
+        info!("'{}' spent {} stat point(s)", player.name, spent);
+
+        author.send_accept(PktType::CHARACTER);
+
+        let player = player.clone();
+
+        match self.rooms.get(&player.current_room) {
+            Some(room) => self.alert_room(room, &player),
+            None => author.send_character(&player),
+        }
+    }
+}
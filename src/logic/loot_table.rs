@@ -0,0 +1,62 @@
+use rand::Rng;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::logic::combat::always;
+
+/// What a monster may carry when it dies. Rolled afresh on every death, so a revived
+/// monster can be looted again.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct LootTable {
+    #[serde(default)]
+    pub gold_min: u16,
+    #[serde(default)]
+    pub gold_max: u16,
+    #[serde(default)]
+    pub drops: Vec<Drop>,
+}
+
+/// An item a monster may drop.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Drop {
+    pub item: Arc<str>,
+    /// Chance, from 0 to 1, that the item drops
+    #[serde(default = "always")]
+    pub chance: f64,
+}
+
+impl LootTable {
+    /// A table that always drops the same gold and items, for monsters that only set
+    /// `gold` and `items` in the map.
+    pub fn fixed(gold: u16, items: &[Arc<str>]) -> Self {
+        Self {
+            gold_min: gold,
+            gold_max: gold,
+            drops: items
+                .iter()
+                .map(|item| Drop {
+                    item: item.clone(),
+                    chance: 1.0,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gold_max == 0 && self.drops.is_empty()
+    }
+
+    /// Roll the gold and items a monster leaves behind.
+    pub fn roll(&self, rng: &mut StdRng) -> (u16, Vec<Arc<str>>) {
+        let gold = rng.random_range(self.gold_min..=self.gold_max.max(self.gold_min));
+        let items = self
+            .drops
+            .iter()
+            .filter(|drop| rng.random_bool(drop.chance.clamp(0.0, 1.0)))
+            .map(|drop| drop.item.clone())
+            .collect();
+
+        (gold, items)
+    }
+}
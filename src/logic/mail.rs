@@ -0,0 +1,65 @@
+use lurk_lcsc::{LurkError, PktError, PktMessage};
+use std::sync::Arc;
+use tracing::info;
+
+use crate::logic::{Client, GameState};
+
+impl GameState {
+    /// Hold a message for a known player who isn't connected, telling the author either way.
+    pub fn queue_mail(&mut self, author: &Client, recipient: Arc<str>, content: PktMessage) {
+        let cap = self.config.mailbox_cap;
+        let mailbox = self.mailboxes.entry(recipient.clone()).or_default();
+
+        if mailbox.len() >= cap {
+            author.send_error(PktError::new(
+                LurkError::OTHER,
+                &format!("{recipient}'s mailbox is full."),
+            ));
+
+            return;
+        }
+
+        mailbox.push_back(content);
+
+        info!(
+            "Queued mail for '{}' ({}/{})",
+            recipient,
+            mailbox.len(),
+            cap
+        );
+
+        if let Some(sender) = self.name_from_client(author) {
+            self.narrate(
+                author,
+                &sender,
+                &format!("{recipient} is away; they'll get your message when they return."),
+            );
+        }
+    }
+
+    /// Hand a returning player everything that was sent while they were away, oldest first.
+    pub fn deliver_mail(&mut self, author: &Client, name: &Arc<str>) {
+        let Some(mailbox) = self.mailboxes.remove(name) else {
+            return;
+        };
+
+        info!(
+            "Delivering {} queued message(s) to '{}'",
+            mailbox.len(),
+            name
+        );
+
+        self.narrate(
+            author,
+            name,
+            &format!(
+                "You have {} message(s) from while you were away.",
+                mailbox.len()
+            ),
+        );
+
+        for message in mailbox {
+            author.send_message(message);
+        }
+    }
+}
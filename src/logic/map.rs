@@ -1,9 +1,28 @@
 use indexmap::IndexSet;
 use lurk_lcsc::{CharacterFlags, PktCharacter, PktConnection, PktRoom, PktType};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::Error};
 use std::{collections::HashMap, fs::File, sync::Arc};
 use tracing::info;
 
+use crate::logic::behaviour::Behaviour;
+use crate::logic::boss::Boss;
+use crate::logic::effects::Effect;
+use crate::logic::loot_table::LootTable;
+
+/// Everything loaded from the map file.
+pub struct World {
+    pub rooms: HashMap<u16, Room>,
+    pub items: HashMap<Arc<str>, Item>,
+}
+
+/// The map file is either a bare list of rooms, or this object with item definitions too.
+#[derive(Deserialize)]
+struct MapFile {
+    #[serde(default)]
+    items: Vec<Item>,
+    rooms: Vec<Room>,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Room {
     pub room_number: u16,
@@ -12,6 +31,9 @@ pub struct Room {
     pub desc: Box<str>,
     pub players: IndexSet<Arc<str>>,
     pub monsters: Option<Vec<Monster>>,
+    /// Names of the items lying on the floor
+    #[serde(default)]
+    pub items: Vec<Arc<str>>,
 }
 
 impl From<&Room> for PktRoom {
@@ -45,7 +67,7 @@ impl From<&Connection> for PktConnection {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Monster {
     pub name: Arc<str>,
     pub current_room: u16,
@@ -55,6 +77,89 @@ pub struct Monster {
     pub defense: u16,
     pub gold: u16,
     pub desc: Box<str>,
+    /// Experience split between the players who defeat it
+    #[serde(default)]
+    pub xp: u32,
+    /// Names of the items the monster drops when looted
+    #[serde(default)]
+    pub items: Vec<Arc<str>>,
+    /// Status effects the monster's blows may inflict
+    #[serde(default)]
+    pub inflicts: Vec<Effect>,
+    #[serde(default)]
+    pub behaviour: Behaviour,
+    /// Gold range and item drops rolled when the monster dies. Monsters without one
+    /// always drop their `gold` and `items`.
+    #[serde(default)]
+    pub loot: LootTable,
+    /// Damage dealt by each player since the monster was last revived
+    #[serde(skip)]
+    pub damage: HashMap<Arc<str>, u32>,
+    /// Players allowed to loot the monster once dead; empty means anyone
+    #[serde(skip)]
+    pub looters: Vec<Arc<str>>,
+    /// Phases the monster goes through if it is a boss
+    #[serde(default)]
+    pub boss: Option<Boss>,
+    /// Summoned by a boss rather than placed in the map; cleared away on revive
+    #[serde(skip)]
+    pub summoned: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    Weapon,
+    Armor,
+    Consumable,
+}
+
+/// An item definition. Weapons and armor apply their modifiers while equipped,
+/// consumables apply them permanently when used.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Item {
+    pub name: Arc<str>,
+    pub kind: ItemKind,
+    pub desc: Box<str>,
+    #[serde(default)]
+    pub attack: u16,
+    #[serde(default)]
+    pub defense: u16,
+    #[serde(default)]
+    pub regen: u16,
+    #[serde(default)]
+    pub health: i16,
+    /// Status effects applied when a consumable is used
+    #[serde(default)]
+    pub effects: Vec<Effect>,
+}
+
+impl std::fmt::Display for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({:?}): {}", self.name, self.kind, self.desc)?;
+
+        let modifiers = [
+            ("attack", self.attack as i32),
+            ("defense", self.defense as i32),
+            ("regen", self.regen as i32),
+            ("health", self.health as i32),
+        ];
+
+        for (stat, value) in modifiers.iter().filter(|(_, value)| *value != 0) {
+            write!(f, " [{stat} {value:+}]")?;
+        }
+
+        for effect in &self.effects {
+            let kind = format!("{:?}", effect.kind).to_lowercase();
+            write!(
+                f,
+                " [{kind} {} for {} ticks]",
+                effect.potency, effect.duration
+            )?;
+        }
+
+        Ok(())
+    }
 }
 
 impl From<&Monster> for PktCharacter {
@@ -111,17 +216,91 @@ impl From<&mut Monster> for PktCharacter {
     }
 }
 
-pub fn build(data: File) -> Result<HashMap<u16, Room>, serde_json::Error> {
+pub fn build(data: File) -> Result<World, serde_json::Error> {
+    info!("Building game map...");
+
+    from_value(serde_json::from_reader(&data)?)
+}
+
+/// Build a world from map JSON held in memory.
+pub fn parse(data: &str) -> Result<World, serde_json::Error> {
+    from_value(serde_json::from_str(data)?)
+}
+
+fn from_value(value: serde_json::Value) -> Result<World, serde_json::Error> {
+    let MapFile {
+        items,
+        rooms: deserialized,
+    } = if value.is_array() {
+        MapFile {
+            items: Vec::new(),
+            rooms: serde_json::from_value(value)?,
+        }
+    } else {
+        serde_json::from_value(value)?
+    };
+
+    let items: HashMap<Arc<str>, Item> = items
+        .into_iter()
+        .map(|item| (item.name.clone(), item))
+        .collect();
+
     let mut rooms: HashMap<u16, Room> = HashMap::new();
 
-    info!("Building game map...");
+    for mut room in deserialized {
+        for monster in room.monsters.iter_mut().flatten() {
+            if monster.loot.is_empty() {
+                monster.loot = LootTable::fixed(monster.gold, &monster.items);
+            }
+
+            if let Some(boss) = &mut monster.boss {
+                boss.base = (monster.attack, monster.defense);
+
+                if boss.phases.windows(2).any(|w| w[0].below <= w[1].below)
+                    || boss.phases.iter().any(|phase| phase.below > 100)
+                {
+                    return Err(serde_json::Error::custom(format!(
+                        "boss '{}' needs phases with falling health thresholds of at most 100",
+                        monster.name
+                    )));
+                }
+            }
 
-    let deserialized: Vec<Room> = serde_json::from_reader(&data)?;
-    info!("Game map built with {} rooms.", deserialized.len());
+            if monster.loot.gold_min > monster.loot.gold_max {
+                return Err(serde_json::Error::custom(format!(
+                    "monster '{}' has gold_min above gold_max",
+                    monster.name
+                )));
+            }
+        }
+
+        // Every placed or dropped item must be defined, or it could never be used
+        let monster_items = room
+            .monsters
+            .iter()
+            .flatten()
+            .flat_map(|m| m.items.iter().chain(m.loot.drops.iter().map(|d| &d.item)));
+
+        if let Some(unknown) = room
+            .items
+            .iter()
+            .chain(monster_items)
+            .find(|name| !items.contains_key(*name))
+        {
+            return Err(serde_json::Error::custom(format!(
+                "room {} references unknown item '{}'",
+                room.room_number, unknown
+            )));
+        }
 
-    for room in deserialized {
         rooms.insert(room.room_number, room);
     }
 
-    Ok(rooms)
+    info!(
+        "Game map built with {} rooms and {} items.",
+        rooms.len(),
+        items.len()
+    );
+
+    Ok(World { rooms, items })
 }
@@ -0,0 +1,249 @@
+use lurk_lcsc::Protocol;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use crate::logic::{ExtendedProtocol, GameState, Outbound};
+
+/// Process-wide metrics, written by the server thread and clients, read by the endpoint.
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::default);
+
+/// Upper bounds, in seconds, of the handler latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.5, 1.0,
+];
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, secs: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if secs <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += secs;
+        self.count += 1;
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    packets_in: BTreeMap<&'static str, u64>,
+    packets_out: BTreeMap<&'static str, u64>,
+    errors: BTreeMap<String, u64>,
+    latency: BTreeMap<&'static str, Histogram>,
+    bytes_in: u64,
+    bytes_out: u64,
+    connected_players: usize,
+    started_players: usize,
+    live_monsters: usize,
+}
+
+#[derive(Default)]
+pub struct Metrics {
+    inner: Mutex<Inner>,
+}
+
+impl Metrics {
+    /// Record a packet the server thread finished handling and how long it took.
+    /// `kind` and `len` come from [`describe`], taken before the packet was consumed.
+    pub fn observe_inbound(&self, kind: &'static str, len: usize, elapsed: Duration) {
+        let mut inner = self.inner.lock().unwrap();
+
+        *inner.packets_in.entry(kind).or_default() += 1;
+        inner.bytes_in += len as u64;
+        inner
+            .latency
+            .entry(kind)
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Record a packet delivered to a client.
+    pub fn observe_outbound(&self, pkt: &Outbound) {
+        let (kind, len) = outbound_name_len(pkt);
+        let mut inner = self.inner.lock().unwrap();
+
+        *inner.packets_out.entry(kind).or_default() += 1;
+        inner.bytes_out += len as u64;
+
+        if let Outbound::Error(err) = pkt {
+            *inner.errors.entry(format!("{:?}", err.error)).or_default() += 1;
+        }
+    }
+
+    /// Record a packet written outside of a `Client`, such as the connection greeting.
+    pub fn observe_raw_outbound(&self, kind: &'static str, len: usize) {
+        let mut inner = self.inner.lock().unwrap();
+
+        *inner.packets_out.entry(kind).or_default() += 1;
+        inner.bytes_out += len as u64;
+    }
+
+    /// Refresh the gauges from the current game state.
+    pub fn observe_state(&self, state: &GameState) {
+        let started = state
+            .players
+            .values()
+            .filter(|p| p.flags.is_started())
+            .count();
+        let live_monsters = state
+            .rooms
+            .values()
+            .filter_map(|room| room.monsters.as_ref())
+            .flatten()
+            .filter(|m| m.health > 0)
+            .count();
+
+        let mut inner = self.inner.lock().unwrap();
+
+        inner.connected_players = state.clients.len();
+        inner.started_players = started;
+        inner.live_monsters = live_monsters;
+    }
+
+    /// Render every metric in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let inner = self.inner.lock().unwrap();
+        let mut out = String::new();
+
+        let name = "zelda_packets_received_total";
+        header(&mut out, name, "counter", "Packets handled by type.");
+        for (kind, count) in &inner.packets_in {
+            let _ = writeln!(out, "{name}{{type=\"{kind}\"}} {count}");
+        }
+
+        let name = "zelda_packets_sent_total";
+        header(&mut out, name, "counter", "Packets sent by type.");
+        for (kind, count) in &inner.packets_out {
+            let _ = writeln!(out, "{name}{{type=\"{kind}\"}} {count}");
+        }
+
+        let name = "zelda_errors_sent_total";
+        header(
+            &mut out,
+            name,
+            "counter",
+            "Error packets sent by Lurk error code.",
+        );
+        for (code, count) in &inner.errors {
+            let _ = writeln!(out, "{name}{{code=\"{code}\"}} {count}");
+        }
+
+        let name = "zelda_handler_seconds";
+        header(&mut out, name, "histogram", "Time spent handling a packet.");
+        for (kind, hist) in &inner.latency {
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(hist.buckets) {
+                let _ = writeln!(
+                    out,
+                    "{name}_bucket{{type=\"{kind}\",le=\"{bound}\"}} {count}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{type=\"{kind}\",le=\"+Inf\"}} {}",
+                hist.count
+            );
+            let _ = writeln!(out, "{name}_sum{{type=\"{kind}\"}} {}", hist.sum);
+            let _ = writeln!(out, "{name}_count{{type=\"{kind}\"}} {}", hist.count);
+        }
+
+        let scalars = [
+            (
+                "zelda_bytes_received_total",
+                "counter",
+                "Bytes received from clients.",
+                inner.bytes_in,
+            ),
+            (
+                "zelda_bytes_sent_total",
+                "counter",
+                "Bytes sent to clients.",
+                inner.bytes_out,
+            ),
+            (
+                "zelda_connected_players",
+                "gauge",
+                "Players with an open connection.",
+                inner.connected_players as u64,
+            ),
+            (
+                "zelda_started_players",
+                "gauge",
+                "Players that have started the game.",
+                inner.started_players as u64,
+            ),
+            (
+                "zelda_live_monsters",
+                "gauge",
+                "Monsters with health remaining.",
+                inner.live_monsters as u64,
+            ),
+        ];
+        for (name, kind, help, value) in scalars {
+            header(&mut out, name, kind, help);
+            let _ = writeln!(out, "{name} {value}");
+        }
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+/// Name and wire size of an incoming event, for labelling metrics. Returns `None` for
+/// ticks, which are not packets and would drown out the real ones.
+pub fn describe(packet: &ExtendedProtocol) -> Option<(&'static str, usize)> {
+    match packet {
+        ExtendedProtocol::Base(_, pkt) => Some((packet_name(pkt), inbound_len(pkt))),
+        ExtendedProtocol::Command(_) => Some(("Command", 0)),
+        ExtendedProtocol::Tick => None,
+    }
+}
+
+fn packet_name(pkt: &Protocol) -> &'static str {
+    match pkt {
+        Protocol::Message(_, _) => "Message",
+        Protocol::ChangeRoom(_, _) => "ChangeRoom",
+        Protocol::Fight(_, _) => "Fight",
+        Protocol::PVPFight(_, _) => "PVPFight",
+        Protocol::Loot(_, _) => "Loot",
+        Protocol::Start(_, _) => "Start",
+        Protocol::Character(_, _) => "Character",
+        Protocol::Leave(_, _) => "Leave",
+        _ => "Other",
+    }
+}
+
+// Wire sizes follow the fixed Lurk layouts: type byte, fixed fields, then variable text.
+
+fn inbound_len(pkt: &Protocol) -> usize {
+    match pkt {
+        Protocol::Message(_, msg) => 67 + msg.message.len(),
+        Protocol::ChangeRoom(_, _) => 3,
+        Protocol::PVPFight(_, _) | Protocol::Loot(_, _) => 33,
+        Protocol::Character(_, character) => 48 + character.description.len(),
+        _ => 1,
+    }
+}
+
+fn outbound_name_len(pkt: &Outbound) -> (&'static str, usize) {
+    match pkt {
+        Outbound::Accept(_) => ("Accept", 2),
+        Outbound::Character(character) => ("Character", 48 + character.description.len()),
+        Outbound::Connection(conn) => ("Connection", 37 + conn.description.len()),
+        Outbound::Error(err) => ("Error", 4 + err.message.len()),
+        Outbound::Message(msg) => ("Message", 67 + msg.message.len()),
+        Outbound::Room(room) => ("Room", 37 + room.description.len()),
+    }
+}
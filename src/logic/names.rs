@@ -0,0 +1,70 @@
+use tracing::info;
+
+use crate::logic::GameState;
+
+/// Punctuation allowed in player names besides letters, digits and spaces.
+const NAME_PUNCTUATION: [char; 3] = ['\'', '-', '_'];
+
+impl GameState {
+    /// Check a character name against the naming rules, returning which one failed.
+    /// A name that exactly matches an existing player is that player returning.
+    pub fn validate_name(&self, name: &str) -> Result<(), String> {
+        let config = &self.config;
+        let length = name.chars().count();
+
+        if name.trim().is_empty() {
+            return Err(String::from("Name can't be empty."));
+        }
+
+        if name.trim() != name {
+            return Err(String::from("Name can't start or end with spaces."));
+        }
+
+        if length < config.name_min_length || length > config.name_max_length {
+            return Err(format!(
+                "Name must be between {} and {} characters.",
+                config.name_min_length, config.name_max_length
+            ));
+        }
+
+        if let Some(c) = name
+            .chars()
+            .find(|c| !c.is_alphanumeric() && *c != ' ' && !NAME_PUNCTUATION.contains(c))
+        {
+            return Err(format!(
+                "Name can't contain '{c}'; use letters, numbers, spaces, ' - or _."
+            ));
+        }
+
+        if name.eq_ignore_ascii_case(&config.narrator_name)
+            || config
+                .reserved_names
+                .iter()
+                .any(|reserved| name.eq_ignore_ascii_case(reserved))
+        {
+            return Err(String::from("That name is reserved."));
+        }
+
+        if self
+            .rooms
+            .values()
+            .filter_map(|room| room.monsters.as_ref())
+            .flatten()
+            .any(|monster| name.eq_ignore_ascii_case(&monster.name))
+        {
+            return Err(String::from("That name belongs to a monster."));
+        }
+
+        if let Some(taken) = self
+            .players
+            .keys()
+            .find(|taken| taken.as_ref() != name && name.eq_ignore_ascii_case(taken))
+        {
+            info!("'{}' collides with existing player '{}'", name, taken);
+
+            return Err(format!("Name is already taken by {taken}."));
+        }
+
+        Ok(())
+    }
+}
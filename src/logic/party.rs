@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::logic::{Channel, Client, GameState};
+
+/// A group of players who fight and share loot together.
+#[derive(Clone, Debug)]
+pub struct Party {
+    pub leader: Arc<str>,
+    /// Members in the order they joined, leader included
+    pub members: Vec<Arc<str>>,
+}
+
+/// Every party in the game and the invitations waiting for an answer.
+#[derive(Default, Debug)]
+pub struct Parties {
+    next_id: u64,
+    parties: HashMap<u64, Party>,
+    /// Party each player belongs to, keyed by player name
+    membership: HashMap<Arc<str>, u64>,
+    /// Who invited each player, keyed by the invited player's name
+    invites: HashMap<Arc<str>, Arc<str>>,
+}
+
+impl Parties {
+    pub fn party_of(&self, name: &str) -> Option<&Party> {
+        self.parties.get(self.membership.get(name)?)
+    }
+
+    /// Whether two players fight together: the same player, or members of one party.
+    pub fn allied(&self, a: &str, b: &str) -> bool {
+        a == b
+            || self
+                .membership
+                .get(a)
+                .is_some_and(|party| self.membership.get(b) == Some(party))
+    }
+
+    /// A player's party members, themselves included. Solo players are a party of one.
+    pub fn members(&self, name: &Arc<str>) -> Vec<Arc<str>> {
+        self.party_of(name)
+            .map(|party| party.members.clone())
+            .unwrap_or_else(|| vec![name.clone()])
+    }
+
+    /// Invite a player into the inviter's party. Returns why not on failure.
+    pub fn invite(
+        &mut self,
+        inviter: &Arc<str>,
+        invitee: &Arc<str>,
+        max: usize,
+    ) -> Result<(), String> {
+        if inviter == invitee {
+            return Err(String::from("You can't invite yourself."));
+        }
+
+        if self.membership.contains_key(invitee) {
+            return Err(format!("{invitee} is already in a party."));
+        }
+
+        if self.members(inviter).len() >= max {
+            return Err(format!("Your party is full; at most {max} can join."));
+        }
+
+        if let Some(party) = self.party_of(inviter)
+            && &party.leader != inviter
+        {
+            return Err(format!("Only {} can invite players.", party.leader));
+        }
+
+        self.invites.insert(invitee.clone(), inviter.clone());
+
+        Ok(())
+    }
+
+    /// Accept a pending invitation, creating the inviter's party if needed. Returns the
+    /// party joined, or why not.
+    pub fn accept(&mut self, name: &Arc<str>, max: usize) -> Result<&Party, String> {
+        let Some(inviter) = self.invites.remove(name) else {
+            return Err(String::from("Nobody has invited you to a party."));
+        };
+
+        if self.membership.contains_key(name) {
+            return Err(String::from("Leave your party first."));
+        }
+
+        // The invite only stands while the inviter could still send it
+        if let Some(party) = self.party_of(&inviter)
+            && party.leader != inviter
+        {
+            return Err(format!("{inviter} no longer leads a party."));
+        }
+
+        if self.members(&inviter).len() >= max {
+            return Err(format!("{inviter}'s party is full."));
+        }
+
+        let id = match self.membership.get(&inviter) {
+            Some(&id) => id,
+            None => {
+                let id = self.next_id;
+                self.next_id += 1;
+
+                self.parties.insert(
+                    id,
+                    Party {
+                        leader: inviter.clone(),
+                        members: vec![inviter.clone()],
+                    },
+                );
+                self.membership.insert(inviter.clone(), id);
+
+                id
+            }
+        };
+
+        let party = self
+            .parties
+            .get_mut(&id)
+            .expect("membership points at a party");
+
+        party.members.push(name.clone());
+        self.membership.insert(name.clone(), id);
+
+        Ok(party)
+    }
+
+    /// Take a player out of their party, handing leadership on and disbanding a party of
+    /// one. Returns the members left behind.
+    pub fn leave(&mut self, name: &Arc<str>) -> Option<Vec<Arc<str>>> {
+        self.invites
+            .retain(|invitee, inviter| invitee != name && inviter != name);
+
+        let id = self.membership.remove(name)?;
+        let party = self.parties.get_mut(&id)?;
+
+        party.members.retain(|member| member != name);
+
+        if &party.leader == name
+            && let Some(next) = party.members.first()
+        {
+            party.leader = next.clone();
+        }
+
+        let remaining = party.members.clone();
+
+        if remaining.len() < 2 {
+            self.parties.remove(&id);
+            remaining.iter().for_each(|member| {
+                self.membership.remove(member);
+            });
+        }
+
+        Some(remaining)
+    }
+
+    /// Forget everyone for whom `keep` returns false.
+    pub fn retain(&mut self, keep: impl Fn(&Arc<str>) -> bool) {
+        let gone: Vec<Arc<str>> = self
+            .membership
+            .keys()
+            .filter(|name| !keep(name))
+            .cloned()
+            .collect();
+
+        for name in gone {
+            self.leave(&name);
+        }
+
+        self.invites
+            .retain(|invitee, inviter| keep(invitee) && keep(inviter));
+    }
+}
+
+impl GameState {
+    /// Handle `/party [invite <player> | accept | leave]`.
+    pub fn handle_party(&mut self, author: &Client, name: &Arc<str>, arg: &str) {
+        let max = self.config.party_max_size;
+
+        let (action, target) = arg
+            .split_once(char::is_whitespace)
+            .map(|(action, target)| (action, target.trim()))
+            .unwrap_or((arg, ""));
+
+        match action.to_ascii_lowercase().as_str() {
+            "" | "list" => {
+                let description = self.describe_party(name);
+                self.narrate(author, name, &description);
+            }
+            "invite" => {
+                let Some((invitee, _)) = self.clients.get_key_value(target).or_else(|| {
+                    self.clients
+                        .iter()
+                        .find(|(n, _)| n.eq_ignore_ascii_case(target))
+                }) else {
+                    self.narrate(author, name, &format!("{target} isn't online."));
+                    return;
+                };
+                let invitee = invitee.clone();
+
+                if let Err(reason) = self.parties.invite(name, &invitee, max) {
+                    self.narrate(author, name, &reason);
+                    return;
+                }
+
+                info!("'{}' invited '{}' to their party", name, invitee);
+
+                self.narrate(
+                    author,
+                    name,
+                    &format!("You invited {invitee} to your party."),
+                );
+
+                if let Some(client) = self.clients.get(&invitee) {
+                    self.narrate(
+                        client,
+                        &invitee,
+                        &format!(
+                            "{name} invited you to their party. \
+                             Message the narrator /party accept to join."
+                        ),
+                    );
+                }
+            }
+            "accept" => {
+                let members = match self.parties.accept(name, max) {
+                    Ok(party) => party.members.clone(),
+                    Err(reason) => {
+                        self.narrate(author, name, &reason);
+                        return;
+                    }
+                };
+
+                info!("'{}' joined a party", name);
+
+                self.party_notice(&members, &format!("{name} joined the party."));
+            }
+            "leave" => {
+                let Some(remaining) = self.parties.leave(name) else {
+                    self.narrate(author, name, "You aren't in a party.");
+                    return;
+                };
+
+                info!("'{}' left their party", name);
+
+                self.narrate(author, name, "You left the party.");
+                self.party_notice(&remaining, &format!("{name} left the party."));
+            }
+            _ => {
+                self.narrate(
+                    author,
+                    name,
+                    "/party invite <player>, accept, leave or list?",
+                );
+            }
+        }
+    }
+
+    /// Relay a message to every member of the sender's party.
+    pub fn party_chat(&self, author: &Client, name: &Arc<str>, message: &str) {
+        let Some(party) = self.parties.party_of(name) else {
+            self.narrate(author, name, "You aren't in a party.");
+            return;
+        };
+
+        self.chat(Channel::Party, name, party.members.iter(), message);
+    }
+
+    /// List a player's party members and where they are.
+    pub fn describe_party(&self, name: &Arc<str>) -> String {
+        let Some(party) = self.parties.party_of(name) else {
+            return String::from("You aren't in a party.");
+        };
+
+        let members: Vec<String> = party
+            .members
+            .iter()
+            .map(|member| {
+                let room = self
+                    .players
+                    .get(member)
+                    .and_then(|player| self.rooms.get(&player.current_room))
+                    .map(|room| room.title.as_ref())
+                    .unwrap_or("somewhere");
+                let leader = if member == &party.leader {
+                    " (leader)"
+                } else {
+                    ""
+                };
+
+                format!("{member}{leader} - {room}")
+            })
+            .collect();
+
+        format!("Your party:\n{}", members.join("\n"))
+    }
+
+    /// Narrate a message to each connected player in a list.
+    fn party_notice(&self, members: &[Arc<str>], message: &str) {
+        for member in members {
+            if let Some(client) = self.clients.get(member) {
+                self.narrate(client, member, message);
+            }
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use lurk_lcsc::{
+    CharacterFlags, PktChangeRoom, PktCharacter, PktFight, PktLeave, PktLoot, PktMessage,
+    PktPVPFight, PktStart, PktType, Protocol,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::logic::audit::AuditLog;
+use crate::logic::{Action, Client, Config, ExtendedProtocol, GameState, MemoryTransport, World};
+
+/// One dispatched event as written to a recording, one JSON object per line.
+#[derive(Serialize, Deserialize)]
+pub struct Record {
+    pub timestamp: Box<str>,
+    /// Connection id of the client that sent the packet; `None` for console commands.
+    pub client: Option<u64>,
+    /// Ticks the server had handled before this event; a replay runs any it is behind.
+    #[serde(default)]
+    pub tick: u64,
+    pub event: Event,
+}
+
+/// The parts of each incoming packet the handlers actually read.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Event {
+    Message {
+        recipient: Box<str>,
+        sender: Box<str>,
+        message: Box<str>,
+    },
+    ChangeRoom {
+        room_number: u16,
+    },
+    Fight,
+    PVPFight {
+        target_name: Box<str>,
+    },
+    Loot {
+        target_name: Box<str>,
+    },
+    Start,
+    Character {
+        name: Box<str>,
+        attack: u16,
+        defense: u16,
+        regen: u16,
+        health: i16,
+        gold: u16,
+        current_room: u16,
+        description: Box<str>,
+    },
+    Leave,
+    Command(Action),
+    /// Combat RNG seed of the recorded session, written when recording starts
+    Seed {
+        seed: u64,
+    },
+}
+
+impl Event {
+    /// Capture a dispatched event. Returns `None` for packets the server ignores and for
+    /// ticks, which records carry as a count instead.
+    pub fn capture(packet: &ExtendedProtocol) -> Option<(Option<u64>, Self)> {
+        let (client, pkt) = match packet {
+            ExtendedProtocol::Base(client, pkt) => (client.id(), pkt),
+            ExtendedProtocol::Command(action) => {
+                return Some((None, Event::Command(action.clone())));
+            }
+            ExtendedProtocol::Tick => return None,
+        };
+
+        let event = match pkt {
+            Protocol::Message(_, content) => Event::Message {
+                recipient: content.recipient.as_ref().into(),
+                sender: content.sender.as_ref().into(),
+                message: content.message.as_ref().into(),
+            },
+            Protocol::ChangeRoom(_, content) => Event::ChangeRoom {
+                room_number: content.room_number,
+            },
+            Protocol::Fight(_, _) => Event::Fight,
+            Protocol::PVPFight(_, content) => Event::PVPFight {
+                target_name: content.target_name.as_ref().into(),
+            },
+            Protocol::Loot(_, content) => Event::Loot {
+                target_name: content.target_name.as_ref().into(),
+            },
+            Protocol::Start(_, _) => Event::Start,
+            Protocol::Character(_, content) => Event::Character {
+                name: content.name.as_ref().into(),
+                attack: content.attack,
+                defense: content.defense,
+                regen: content.regen,
+                health: content.health,
+                gold: content.gold,
+                current_room: content.current_room,
+                description: content.description.clone(),
+            },
+            Protocol::Leave(_, _) => Event::Leave,
+            _ => return None,
+        };
+
+        Some((Some(client), event))
+    }
+
+    /// Feed the event back through the same handler the server would have used.
+    pub fn apply(self, state: &mut GameState, author: Client) {
+        match self {
+            Event::Message {
+                recipient,
+                sender,
+                message,
+            } => {
+                let mut content = PktMessage::server(&recipient, &message);
+                content.sender = sender.as_ref().into();
+                state.handle_message(author, content);
+            }
+            Event::ChangeRoom { room_number } => {
+                let content = PktChangeRoom {
+                    packet_type: PktType::CHANGEROOM,
+                    room_number,
+                };
+                state.handle_change_room(author, content);
+            }
+            Event::Fight => state.handle_fight(author, PktFight::default()),
+            Event::PVPFight { target_name } => {
+                let content = PktPVPFight {
+                    packet_type: PktType::PVPFIGHT,
+                    target_name: target_name.as_ref().into(),
+                };
+                state.handle_pvp_fight(author, content);
+            }
+            Event::Loot { target_name } => {
+                let content = PktLoot {
+                    packet_type: PktType::LOOT,
+                    target_name: target_name.as_ref().into(),
+                };
+                state.handle_loot(author, content);
+            }
+            Event::Start => state.handle_start(author, PktStart::default()),
+            Event::Character {
+                name,
+                attack,
+                defense,
+                regen,
+                health,
+                gold,
+                current_room,
+                description,
+            } => {
+                let content = PktCharacter {
+                    author: None,
+                    packet_type: PktType::CHARACTER,
+                    name: name.into(),
+                    flags: CharacterFlags::empty(), // The handler always overwrites flags
+                    attack,
+                    defense,
+                    regen,
+                    health,
+                    gold,
+                    current_room,
+                    description_len: description.len() as u16,
+                    description,
+                };
+                state.handle_character(author, content);
+            }
+            Event::Leave => state.handle_leave(author, PktLeave::default()),
+            Event::Command(action) => state.handle_command(action),
+            Event::Seed { seed } => state.reseed(seed),
+        }
+    }
+}
+
+/// Writes every dispatched event to a recording file, created or truncated on startup.
+pub struct Recorder {
+    writer: BufWriter<File>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        // One run per file: client ids restart every run, so appending would mix players
+        let file = File::create(path)?;
+
+        Ok(Self {
+            writer: BufWriter::new(file),
+        })
+    }
+
+    /// Record a packet about to be handled, along with the ticks handled so far.
+    pub fn record(&mut self, packet: &ExtendedProtocol, tick: u64) {
+        let Some((client, event)) = Event::capture(packet) else {
+            return;
+        };
+
+        self.write(client, tick, event);
+    }
+
+    /// Note the combat RNG seed so a replay rolls the same dice.
+    pub fn record_seed(&mut self, seed: u64) {
+        self.write(None, 0, Event::Seed { seed });
+    }
+
+    fn write(&mut self, client: Option<u64>, tick: u64, event: Event) {
+        let record = Record {
+            timestamp: chrono::Local::now().to_rfc3339().into(),
+            client,
+            tick,
+            event,
+        };
+
+        // Flush every line so a crash still leaves a usable recording
+        let result = serde_json::to_writer(&mut self.writer, &record)
+            .map_err(io::Error::from)
+            .and_then(|_| self.writer.write_all(b"\n"))
+            .and_then(|_| self.writer.flush());
+
+        if let Err(e) = result {
+            error!("Failed to write recording: {}", e);
+        }
+    }
+}
+
+/// Replay a recording against a fresh game state, printing every packet the server sends.
+pub fn replay(path: &Path, world: World, config: Arc<Config>) -> io::Result<()> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut state = GameState::new(world, config);
+    state.audit = AuditLog::disabled(); // A replay must not pollute the live audit trail
+    let mut clients: BTreeMap<u64, (Client, MemoryTransport)> = BTreeMap::new();
+
+    info!("Replaying {}", path.display());
+
+    for (line_no, line) in reader.lines().enumerate() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record: Record = match serde_json::from_str(&line) {
+            Ok(record) => record,
+            Err(e) => {
+                warn!("Skipping line {}: {}", line_no + 1, e);
+                continue;
+            }
+        };
+
+        // Run the ticks that passed between this event and the one before
+        while state.tick < record.tick {
+            state.handle_tick();
+        }
+
+        let summary = serde_json::to_string(&record.event).unwrap_or_default();
+
+        match record.client {
+            Some(id) => {
+                println!("{} [client {}] -> {}", record.timestamp, id, summary);
+
+                let (client, _) = clients.entry(id).or_insert_with(Client::memory);
+                let client = client.clone();
+
+                record.event.apply(&mut state, client);
+            }
+            None => {
+                println!("{} [console] -> {}", record.timestamp, summary);
+
+                match record.event {
+                    Event::Command(action) => state.handle_command(action),
+                    Event::Seed { seed } => state.reseed(seed),
+                    _ => {}
+                }
+            }
+        }
+
+        for (id, (_, transport)) in &clients {
+            for pkt in transport.take() {
+                println!("    [client {}] <- {}", id, pkt);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,112 +1,210 @@
 use lurk_lcsc::LurkError;
 use lurk_lcsc::PktCharacter;
 use lurk_lcsc::PktMessage;
-use lurk_lcsc::{PktConnection, PktError, send_error, send_to};
-use std::collections::HashMap;
-use std::net::TcpStream;
+use lurk_lcsc::{PktConnection, PktError};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use tracing::error;
 use tracing::info;
 use tracing::trace;
 
-use crate::logic::{Config, Room};
+use crate::logic::audit::AuditLog;
+use crate::logic::effects::StatusEffects;
+use crate::logic::party::Parties;
+use crate::logic::{Client, Config, Inventory, Item, Progress, Room, World};
+
+/// Player chat channels, each of which a player can opt out of.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Channel {
+    /// Everyone in the sender's room
+    Say,
+    /// Everyone in the game
+    Shout,
+    /// Everyone in the sender's party
+    Party,
+}
+
+impl std::str::FromStr for Channel {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "say" => Ok(Channel::Say),
+            "shout" => Ok(Channel::Shout),
+            "party" => Ok(Channel::Party),
+            _ => Err(()),
+        }
+    }
+}
 
 /// Central game state holding all players, rooms, and server configuration.
 pub struct GameState {
     pub players: HashMap<Arc<str>, PktCharacter>,
+    /// Connected clients keyed by player name. Disconnected players have no entry.
+    pub clients: HashMap<Arc<str>, Client>,
     pub rooms: HashMap<u16, Room>,
+    /// Item definitions from the map, keyed by name
+    pub items: HashMap<Arc<str>, Item>,
+    /// Items carried by each player, keyed by player name
+    pub inventories: HashMap<Arc<str>, Inventory>,
+    /// Level and XP of each player, keyed by player name
+    pub progress: HashMap<Arc<str>, Progress>,
+    /// Channels each player has opted out of, keyed by player name
+    pub muted: HashMap<Arc<str>, HashSet<Channel>>,
+    /// Messages waiting for offline players, oldest first, keyed by player name
+    pub mailboxes: HashMap<Arc<str>, VecDeque<PktMessage>>,
+    /// Ticks elapsed since the server started
+    pub tick: u64,
+    /// Tick at which each dead player respawns, keyed by player name
+    pub respawns: HashMap<Arc<str>, u64>,
+    /// Status effects on each player, keyed by player name
+    pub effects: HashMap<Arc<str>, StatusEffects>,
+    /// Monster each player has chosen to fight, keyed by player name
+    pub targets: HashMap<Arc<str>, Arc<str>>,
+    pub parties: Parties,
+    /// Kills so far by each group of fighters, keyed by their sorted names, for handing
+    /// out loot in turn
+    pub loot_turns: HashMap<Vec<Arc<str>>, u64>,
+    /// Seed the combat RNG started from, so a session can be replayed
+    pub seed: u64,
+    /// Source of randomness for combat
+    pub rng: StdRng,
     pub config: Arc<Config>,
+    pub audit: AuditLog,
 }
 
 impl GameState {
-    pub fn new(rooms: HashMap<u16, Room>, config: Arc<Config>) -> Self {
+    pub fn new(world: World, config: Arc<Config>) -> Self {
+        let audit = match &config.audit_path {
+            Some(path) => AuditLog::open(path).unwrap_or_else(|e| {
+                error!("Failed to open audit log, auditing disabled: {}", e);
+                AuditLog::disabled()
+            }),
+            None => AuditLog::disabled(),
+        };
+
+        let seed = config.combat.seed.unwrap_or_else(rand::random);
+
+        info!("Combat RNG seeded with {}", seed);
+
         Self {
             players: HashMap::new(),
-            rooms,
+            clients: HashMap::new(),
+            rooms: world.rooms,
+            items: world.items,
+            inventories: HashMap::new(),
+            progress: HashMap::new(),
+            muted: HashMap::new(),
+            mailboxes: HashMap::new(),
+            tick: 0,
+            respawns: HashMap::new(),
+            effects: HashMap::new(),
+            targets: HashMap::new(),
+            parties: Parties::default(),
+            loot_turns: HashMap::new(),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
             config,
+            audit,
         }
     }
 
+    /// Restart the combat RNG from a seed, e.g. the one a recording was made with.
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
     /// Check that a player is started and ready. Sends an error to the author if not.
     /// Returns `true` if the player is started and ready.
-    pub fn ensure_started(player: &PktCharacter, author: &Arc<TcpStream>) -> bool {
+    pub fn ensure_started(player: &PktCharacter, author: &Client) -> bool {
         if !player.flags.is_started() && !player.flags.is_ready() {
-            send_error!(
-                author.clone(),
-                PktError::new(LurkError::NOTREADY, "Start the game first!")
-            );
+            author.send_error(PktError::new(LurkError::NOTREADY, "Start the game first!"));
             return false;
         }
         true
     }
 
     /// Send players, and monsters to a client.
-    pub fn send_room_contents(&self, author: &Arc<TcpStream>, room: &Room) {
+    pub fn send_room_contents(&self, author: &Client, room: &Room) {
         // Send all players in the room
         for name in &room.players {
             if let Some(player) = self.players.get(name) {
-                let _ = send_to(author.as_ref(), player);
+                author.send_character(player);
             }
         }
 
         // Send all monsters in the room
         if let Some(monsters) = &room.monsters {
             for monster in monsters {
-                let pkt = PktCharacter::from(monster);
-                let _ = send_to(author.as_ref(), &pkt);
+                author.send_character(&PktCharacter::from(monster));
             }
         }
+
+        // Lurk has no item packets, so narrate what's on the floor
+        if !room.items.is_empty()
+            && let Some(name) = self.name_from_client(author)
+        {
+            let items: Vec<&str> = room.items.iter().map(|item| item.as_ref()).collect();
+
+            author.send_message(PktMessage::narrator(
+                &name,
+                &format!("On the ground you see: {}", items.join(", ")),
+            ));
+        }
     }
 
     /// Send all connection exits for a room to a client.
-    pub fn send_connections(&self, author: &Arc<TcpStream>, room_id: u16) {
+    pub fn send_connections(&self, author: &Client, room_id: u16) {
         let Some(room) = self.rooms.get(&room_id) else {
             error!("No exits for room {}", room_id);
             return;
         };
 
         for conn in room.connections.values() {
-            let pkt = PktConnection::from(conn);
-            let _ = send_to(author.as_ref(), &pkt);
+            author.send_connection(PktConnection::from(conn));
         }
     }
 
-    /// Find a player by their TCP stream.
-    pub fn player_from_stream(
+    /// Find the name of the player controlled by a client.
+    pub fn name_from_client(&self, client: &Client) -> Option<Arc<str>> {
+        self.clients
+            .iter()
+            .find(|(_, c)| *c == client)
+            .map(|(name, _)| name.clone())
+    }
+
+    /// Find a player by their client handle.
+    pub fn player_from_client(
         &mut self,
-        stream: &Arc<TcpStream>,
+        client: &Client,
     ) -> Option<(&Arc<str>, &mut PktCharacter)> {
-        self.players.iter_mut().find(|(_, player)| {
-            player
-                .author
-                .as_ref()
-                .is_some_and(|author| Arc::ptr_eq(author, stream))
-        })
+        let name = self.name_from_client(client)?;
+
+        self.players.iter_mut().find(|(key, _)| **key == name)
     }
 
     /// Internal helper: send a constructed message to each named player.
     fn send_to_players<'a>(
-        players: &'a HashMap<Arc<str>, PktCharacter>,
+        clients: &HashMap<Arc<str>, Client>,
         names: impl Iterator<Item = &'a Arc<str>>,
         msg_fn: impl Fn(&Arc<str>) -> PktMessage,
     ) {
         for name in names {
-            let Some(player) = players.get(name) else {
-                continue;
-            };
-            let Some(author) = player.author.as_ref() else {
+            let Some(author) = clients.get(name) else {
                 continue;
             };
 
-            let msg = msg_fn(name);
-            let _ = send_to(author.as_ref(), &msg);
+            author.send_message(msg_fn(name));
         }
     }
 
     /// Broadcast a message to all connected players.
     pub fn broadcast(&self, message: String) {
         info!("Sending message: {}", message);
-        GameState::send_to_players(&self.players, self.players.keys(), |name| {
+        GameState::send_to_players(&self.clients, self.players.keys(), |name| {
             PktMessage::server(name, &message)
         });
     }
@@ -117,7 +215,7 @@ impl GameState {
             "[ROOM MESSAGE] Messaging room {}: {}",
             room.room_number, message
         );
-        GameState::send_to_players(&self.players, room.players.iter(), |name| {
+        GameState::send_to_players(&self.clients, room.players.iter(), |name| {
             if narration {
                 PktMessage::narrator(name, &message)
             } else {
@@ -126,6 +224,31 @@ impl GameState {
         });
     }
 
+    /// Relay a player's message on a chat channel to the given players, skipping those who
+    /// muted it. The sender always sees their own message.
+    pub fn chat<'a>(
+        &self,
+        channel: Channel,
+        sender: &Arc<str>,
+        names: impl Iterator<Item = &'a Arc<str>>,
+        message: &str,
+    ) {
+        info!("[{:?}] {}: {}", channel, sender, message);
+
+        let listening = names.filter(|name| {
+            *name == sender
+                || !self
+                    .muted
+                    .get(*name)
+                    .is_some_and(|muted| muted.contains(&channel))
+        });
+
+        GameState::send_to_players(&self.clients, listening, |name| PktMessage {
+            sender: sender.as_ref().into(),
+            ..PktMessage::server(name, message)
+        });
+    }
+
     /// Alert all players in the current room of a character change by sending a Character packet
     /// to each player in the room.
     pub fn alert_room(&self, room: &Room, alert: &PktCharacter) {
@@ -134,14 +257,11 @@ impl GameState {
         room.players.iter().for_each(|name| {
             trace!("Alerting player: '{}'", name);
 
-            let Some(player) = self.players.get(name) else {
-                return;
-            };
-            let Some(author) = player.author.as_ref() else {
+            let Some(author) = self.clients.get(name) else {
                 return;
             };
 
-            let _ = send_to(author.as_ref(), alert);
+            author.send_character(alert);
         });
     }
 }
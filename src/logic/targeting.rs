@@ -0,0 +1,62 @@
+use std::sync::Arc;
+use tracing::info;
+
+use crate::logic::{Client, GameState};
+
+/// Which Lurk packet, besides the `/target` chat command, may select a fight target.
+/// `PktFight` has no target field, so a packet that carries a name is borrowed for it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TargetPacket {
+    /// Only `/target` selects a target
+    #[default]
+    None,
+    /// A `PktLoot` naming a living monster selects it
+    Loot,
+    /// A `PktPVPFight` naming a monster selects it
+    PvpFight,
+}
+
+impl GameState {
+    /// Choose which monster a player attacks on their next fight, telling the player when
+    /// there is no such living monster in their room.
+    pub fn set_target(&mut self, author: &Client, name: &Arc<str>, target: &str) {
+        let Some(current_room) = self.players.get(name).map(|p| p.current_room) else {
+            return;
+        };
+
+        let Some(monster) = self
+            .rooms
+            .get(&current_room)
+            .and_then(|room| room.monsters.as_ref())
+            .into_iter()
+            .flatten()
+            .find(|m| m.health > 0 && m.name.eq_ignore_ascii_case(target))
+        else {
+            self.narrate(author, name, &format!("There is no living {target} here."));
+            return;
+        };
+
+        let monster = monster.name.clone();
+
+        info!("'{}' is targeting '{}'", name, monster);
+
+        self.narrate(author, name, &format!("You set your sights on {monster}."));
+        self.targets.insert(name.clone(), monster);
+    }
+
+    /// The index of the monster a player's fight should hit in a room: their chosen target
+    /// if it is still alive there, otherwise the living monster with the lowest health.
+    pub fn fight_target(&self, name: &Arc<str>, room: u16) -> Option<usize> {
+        let monsters = self.rooms.get(&room)?.monsters.as_ref()?;
+        let alive = || monsters.iter().enumerate().filter(|(_, m)| m.health > 0);
+
+        let chosen = self
+            .targets
+            .get(name)
+            .and_then(|target| alive().find(|(_, m)| &m.name == target));
+
+        chosen
+            .or_else(|| alive().min_by_key(|(_, m)| (m.health, m.name.clone())))
+            .map(|(idx, _)| idx)
+    }
+}
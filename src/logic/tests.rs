@@ -0,0 +1,1035 @@
+use lurk_lcsc::{
+    CharacterFlags, PktChangeRoom, PktCharacter, PktFight, PktLeave, PktLoot, PktMessage, PktStart,
+    PktType,
+};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::logic::behaviour::Behaviour;
+use crate::logic::combat::{CombatRules, Retaliation};
+use crate::logic::distribution::LootMode;
+use crate::logic::effects::{Effect, EffectKind};
+use crate::logic::filter::{ContentFilter, FilterMode};
+use crate::logic::loot_table::{Drop, LootTable};
+use crate::logic::party::Parties;
+use crate::logic::record::Record;
+use crate::logic::{
+    Action, Client, Config, GameState, MemoryTransport, Outbound, TargetPacket, map,
+};
+
+const MAP: &str = r#"{
+    "items": [
+        { "name": "Kokiri Sword", "kind": "weapon", "desc": "A small sword.", "attack": 5 },
+        { "name": "Red Potion", "kind": "consumable", "desc": "Restores health.", "health": 20 }
+    ],
+    "rooms": [
+        {
+            "room_number": 0,
+            "title": "Clearing",
+            "connections": {
+                "1": { "room_number": 1, "title": "Hall", "desc_short": "A dark hall." }
+            },
+            "desc": "A quiet clearing.",
+            "players": [],
+            "monsters": [
+                {
+                    "name": "Deku Baba",
+                    "current_room": 0,
+                    "max_health": 8,
+                    "health": 8,
+                    "attack": 3,
+                    "defense": 1,
+                    "gold": 2,
+                    "xp": 10,
+                    "desc": "A snapping plant."
+                }
+            ]
+        },
+        {
+            "room_number": 1,
+            "title": "Hall",
+            "connections": {
+                "0": { "room_number": 0, "title": "Clearing", "desc_short": "A quiet clearing." }
+            },
+            "desc": "A dark hall.",
+            "players": [],
+            "monsters": null,
+            "items": ["Kokiri Sword"]
+        }
+    ]
+}"#;
+
+fn config() -> Arc<Config> {
+    Arc::new(Config {
+        cmd_prefix: "!".into(),
+        map_path: Path::new("unused.json").into(),
+        description: "Test server".into(),
+        stat_limit: 65535,
+        initial_points: 100,
+        major_rev: 2,
+        minor_rev: 3,
+        help_cmd: "".into(),
+        tls_cert_path: None,
+        tls_key_path: None,
+        metrics_addr: None,
+        audit_path: None,
+        narrator_name: "Narrator".into(),
+        mailbox_cap: 2,
+        filter: ContentFilter::default(),
+        name_min_length: 2,
+        name_max_length: 32,
+        reserved_names: vec!["Server".into()],
+        xp_thresholds: vec![10, 30],
+        points_per_level: 5,
+        death_gold_penalty: 0.5,
+        respawn_delay: 2,
+        respawn_health: 100,
+        target_packet: TargetPacket::None,
+        retaliation: Retaliation::All,
+        combat: CombatRules {
+            seed: Some(0),
+            ..CombatRules::default()
+        },
+        wander_interval: 2,
+        loot_mode: LootMode::FreeForAll,
+        party_max_size: 3,
+        party_battles: false,
+    })
+}
+
+fn state() -> GameState {
+    let world = map::parse(MAP).expect("fixture map is valid");
+
+    GameState::new(world, config())
+}
+
+fn character(name: &str, attack: u16, defense: u16, regen: u16) -> PktCharacter {
+    PktCharacter {
+        author: None,
+        packet_type: PktType::CHARACTER,
+        name: name.into(),
+        flags: CharacterFlags::empty(),
+        attack,
+        defense,
+        regen,
+        health: 100,
+        gold: 0,
+        current_room: 0,
+        description_len: 4,
+        description: "Test".into(),
+    }
+}
+
+/// Create a character and start the game, discarding the packets sent along the way.
+fn join(state: &mut GameState, name: &str) -> (Client, MemoryTransport) {
+    let (client, outbox) = Client::memory();
+
+    state.handle_character(client.clone(), character(name, 50, 25, 25));
+    state.handle_start(client.clone(), PktStart::default());
+    outbox.take();
+
+    (client, outbox)
+}
+
+fn loot(target: &str) -> PktLoot {
+    PktLoot {
+        packet_type: PktType::LOOT,
+        target_name: target.into(),
+    }
+}
+
+fn kinds(out: &[Outbound]) -> Vec<&'static str> {
+    out.iter()
+        .map(|pkt| match pkt {
+            Outbound::Accept(_) => "Accept",
+            Outbound::Character(_) => "Character",
+            Outbound::Connection(_) => "Connection",
+            Outbound::Error(_) => "Error",
+            Outbound::Message(_) => "Message",
+            Outbound::Room(_) => "Room",
+        })
+        .collect()
+}
+
+#[test]
+fn full_game_flow() {
+    let mut state = state();
+    let (client, outbox) = Client::memory();
+
+    state.handle_character(client.clone(), character("Link", 50, 25, 25));
+    assert_eq!(kinds(&outbox.take()), ["Accept", "Character"]);
+
+    state.handle_start(client.clone(), PktStart::default());
+    assert_eq!(
+        kinds(&outbox.take()),
+        [
+            "Character",
+            "Message",
+            "Room",
+            "Connection",
+            "Character",
+            "Character"
+        ]
+    );
+    assert!(state.rooms[&0].players.contains("Link"));
+
+    state.handle_fight(client.clone(), PktFight::default());
+    assert_eq!(
+        kinds(&outbox.take()),
+        ["Character", "Character", "Message", "Message"] // XP gained, level up
+    );
+
+    let baba = &state.rooms[&0].monsters.as_ref().unwrap()[0];
+    assert!(baba.health <= 0, "50 attack should one-shot the Deku Baba");
+
+    let gold_before = state.players["Link"].gold;
+
+    state.handle_loot(client.clone(), loot("Deku Baba"));
+    assert_eq!(kinds(&outbox.take()), ["Character", "Character"]);
+    assert_eq!(state.players["Link"].gold, gold_before + 2);
+
+    state.handle_loot(client.clone(), loot("Deku Baba"));
+    assert_eq!(kinds(&outbox.take()), ["Error"]);
+
+    let change = PktChangeRoom {
+        packet_type: PktType::CHANGEROOM,
+        room_number: 1,
+    };
+
+    state.handle_change_room(client.clone(), change);
+    assert_eq!(
+        kinds(&outbox.take()),
+        [
+            "Room",
+            "Character",
+            "Character",
+            "Connection",
+            "Character",
+            "Message"
+        ]
+    );
+    assert_eq!(state.players["Link"].current_room, 1);
+    assert!(!state.rooms[&0].players.contains("Link"));
+    assert!(state.rooms[&1].players.contains("Link"));
+
+    state.handle_leave(client.clone(), PktLeave::default());
+    assert!(outbox.take().is_empty());
+    assert!(outbox.is_closed());
+    assert!(!state.clients.contains_key("Link"));
+}
+
+#[test]
+fn invalid_stats_are_rejected() {
+    let mut state = state();
+    let (client, outbox) = Client::memory();
+
+    state.handle_character(client, character("Link", 100, 1, 0));
+
+    assert_eq!(kinds(&outbox.take()), ["Error"]);
+    assert!(state.players.is_empty());
+}
+
+#[test]
+fn unknown_clients_are_ignored() {
+    let mut state = state();
+    let (client, outbox) = Client::memory();
+
+    state.handle_fight(client.clone(), PktFight::default());
+    state.handle_loot(client, loot("Deku Baba"));
+
+    assert!(outbox.take().is_empty());
+}
+
+#[test]
+fn messages_reach_the_recipient() {
+    let mut state = state();
+    let (link, link_out) = join(&mut state, "Link");
+    let (_zelda, zelda_out) = join(&mut state, "Zelda");
+    link_out.take();
+
+    state.handle_message(link.clone(), PktMessage::server("Zelda", "Hey, listen!"));
+
+    assert!(link_out.take().is_empty());
+    assert_eq!(kinds(&zelda_out.take()), ["Message"]);
+}
+
+#[test]
+fn recorded_events_replay_through_handlers() {
+    let lines = [
+        r#"{"timestamp":"t0","client":7,"event":{"type":"Character","name":"Link","attack":50,"defense":25,"regen":25,"health":100,"gold":0,"current_room":0,"description":"Test"}}"#,
+        r#"{"timestamp":"t1","client":7,"event":{"type":"Start"}}"#,
+        r#"{"timestamp":"t2","client":7,"event":{"type":"Fight"}}"#,
+    ];
+
+    let mut state = state();
+    let (client, outbox) = Client::memory();
+
+    for line in lines {
+        let record: Record = serde_json::from_str(line).expect("recording line is valid");
+        record.event.apply(&mut state, client.clone());
+    }
+
+    assert!(state.players["Link"].flags.is_started());
+    assert!(state.rooms[&0].monsters.as_ref().unwrap()[0].health <= 0);
+    assert_eq!(kinds(&outbox.take()).len(), 12);
+}
+
+/// A chat command addressed to the narrator.
+fn chat(message: &str) -> PktMessage {
+    PktMessage::server("Narrator", message)
+}
+
+#[test]
+fn items_can_be_taken_equipped_and_dropped() {
+    let mut state = state();
+    let (link, outbox) = join(&mut state, "Link");
+    let change = PktChangeRoom {
+        packet_type: PktType::CHANGEROOM,
+        room_number: 1,
+    };
+    state.handle_change_room(link.clone(), change);
+    outbox.take();
+
+    state.handle_message(link.clone(), chat("/take kokiri sword"));
+    assert!(state.rooms[&1].items.is_empty());
+    assert_eq!(state.inventories["Link"].items[0].as_ref(), "Kokiri Sword");
+    outbox.take();
+
+    state.handle_message(link.clone(), chat("/equip Kokiri Sword"));
+    assert_eq!(state.players["Link"].attack, 55);
+    assert_eq!(kinds(&outbox.take()), ["Message", "Character"]);
+
+    state.handle_message(link.clone(), chat("/use Kokiri Sword"));
+    assert_eq!(state.players["Link"].attack, 55);
+    outbox.take();
+
+    state.handle_message(link.clone(), chat("/drop Kokiri Sword"));
+    assert_eq!(state.players["Link"].attack, 50);
+    assert!(state.inventories["Link"].items.is_empty());
+    assert_eq!(state.rooms[&1].items.len(), 1);
+}
+
+#[test]
+fn looting_hands_over_monster_items() {
+    let mut state = state();
+    let (link, outbox) = join(&mut state, "Link");
+    state.rooms.get_mut(&0).unwrap().monsters.as_mut().unwrap()[0]
+        .loot
+        .drops
+        .push(Drop {
+            item: "Red Potion".into(),
+            chance: 1.0,
+        });
+
+    state.handle_fight(link.clone(), PktFight::default());
+    state.handle_loot(link.clone(), loot("Deku Baba"));
+    assert_eq!(state.inventories["Link"].items[0].as_ref(), "Red Potion");
+    outbox.take();
+
+    let health = state.players["Link"].health;
+
+    state.handle_message(link.clone(), chat("/use red potion"));
+    assert_eq!(state.players["Link"].health, health + 20);
+    assert!(state.inventories["Link"].items.is_empty());
+}
+
+#[test]
+fn narrator_answers_chat_commands() {
+    let mut state = state();
+    let (link, link_out) = join(&mut state, "Link");
+    let (_zelda, zelda_out) = join(&mut state, "Zelda");
+    link_out.take();
+
+    for command in ["/look", "who", "/help", "/dance"] {
+        state.handle_message(link.clone(), chat(command));
+
+        let out = link_out.take();
+        assert_eq!(kinds(&out), ["Message"], "{command}");
+    }
+
+    state.handle_message(link.clone(), chat("/look"));
+    let Outbound::Message(reply) = &link_out.take()[0] else {
+        panic!("expected a narrator message");
+    };
+    assert!(reply.narration);
+    assert!(reply.message.contains("Deku Baba (8/8 hp)"));
+    assert!(reply.message.contains("Zelda"));
+
+    assert!(zelda_out.take().is_empty());
+}
+
+#[test]
+fn narrator_name_is_reserved() {
+    let mut state = state();
+    let (client, outbox) = Client::memory();
+
+    state.handle_character(client, character("narrator", 50, 25, 25));
+
+    assert_eq!(kinds(&outbox.take()), ["Error"]);
+    assert!(state.players.is_empty());
+}
+
+#[test]
+fn say_reaches_the_room_and_shout_reaches_everyone() {
+    let mut state = state();
+    let (link, link_out) = join(&mut state, "Link");
+    let (zelda, zelda_out) = join(&mut state, "Zelda");
+    let (navi, navi_out) = join(&mut state, "Navi");
+    let change = PktChangeRoom {
+        packet_type: PktType::CHANGEROOM,
+        room_number: 1,
+    };
+    state.handle_change_room(zelda.clone(), change);
+    link_out.take();
+    zelda_out.take();
+    navi_out.take();
+
+    state.handle_message(link.clone(), chat("/say Hey, listen!"));
+    assert_eq!(kinds(&link_out.take()), ["Message"]);
+    assert_eq!(kinds(&navi_out.take()), ["Message"]);
+    assert!(zelda_out.take().is_empty());
+
+    state.handle_message(navi.clone(), chat("/mute shout"));
+    navi_out.take();
+
+    state.handle_message(link.clone(), chat("/shout Over here!"));
+    let Outbound::Message(heard) = &zelda_out.take()[0] else {
+        panic!("expected a shout");
+    };
+    assert_eq!(heard.sender.as_ref(), "Link");
+    assert!(!heard.narration);
+    assert!(navi_out.take().is_empty());
+    assert_eq!(kinds(&link_out.take()), ["Message"]);
+}
+
+#[test]
+fn offline_players_get_their_mail_on_return() {
+    let mut state = state();
+    let (link, link_out) = join(&mut state, "Link");
+    let (zelda, _) = join(&mut state, "Zelda");
+    state.handle_leave(zelda, PktLeave::default());
+    link_out.take();
+
+    let (stranger, stranger_out) = Client::memory();
+    state.handle_message(stranger, PktMessage::server("Zelda", "Boo"));
+    assert_eq!(kinds(&stranger_out.take()), ["Error"]);
+    assert!(!state.mailboxes.contains_key("Zelda"));
+
+    for text in ["First", "Second", "Third"] {
+        state.handle_message(link.clone(), PktMessage::server("Zelda", text));
+    }
+    assert_eq!(kinds(&link_out.take()), ["Message", "Message", "Error"]);
+    assert_eq!(state.mailboxes["Zelda"].len(), 2);
+    assert_eq!(state.mailboxes["Zelda"][0].sender.as_ref(), "Link");
+
+    let (zelda, zelda_out) = Client::memory();
+    state.handle_character(zelda.clone(), character("Zelda", 50, 25, 25));
+    state.handle_start(zelda, PktStart::default());
+
+    let out = zelda_out.take();
+    let mail: Vec<&str> = out[out.len() - 2..]
+        .iter()
+        .filter_map(|pkt| match pkt {
+            Outbound::Message(msg) => Some(msg.message.as_ref()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(mail, ["First", "Second"]);
+    assert!(!state.mailboxes.contains_key("Zelda"));
+}
+
+fn filter(mode: FilterMode) -> ContentFilter {
+    ContentFilter {
+        mode,
+        words: ContentFilter::word_pattern(["heck", "# comment"].into_iter()),
+        rules: vec![regex::Regex::new(r"https?://[\w./]+").unwrap()],
+        max_repeat: Some(3),
+        max_length: Some(40),
+    }
+}
+
+#[test]
+fn filter_masks_rejects_or_logs() {
+    let text = "What the HECK is at http://spam.example?!!!!!!";
+
+    let masked = filter(FilterMode::Mask).apply("message", text).unwrap();
+    assert_eq!(masked, "What the **** is at *******************?");
+
+    let rejected = filter(FilterMode::Reject).apply("message", text);
+    assert_eq!(rejected.unwrap_err(), "Your message is too long.");
+
+    let logged = filter(FilterMode::Log).apply("message", text).unwrap();
+    assert_eq!(logged, text);
+
+    let clean = filter(FilterMode::Reject).apply("message", "Check the hecking chest");
+    assert_eq!(clean.unwrap(), "Check the hecking chest");
+}
+
+#[test]
+fn filter_applies_to_names_and_messages() {
+    let mut config = (*config()).clone();
+    config.filter = filter(FilterMode::Reject);
+    let mut state = GameState::new(map::parse(MAP).unwrap(), Arc::new(config));
+
+    let (client, outbox) = Client::memory();
+    state.handle_character(client, character("Heck", 50, 25, 25));
+    assert_eq!(kinds(&outbox.take()), ["Error"]);
+    assert!(state.players.is_empty());
+
+    let (link, link_out) = join(&mut state, "Link");
+    let (_zelda, zelda_out) = join(&mut state, "Zelda");
+    link_out.take();
+
+    state.handle_message(link, PktMessage::server("Zelda", "Heeeeeey"));
+    assert_eq!(kinds(&link_out.take()), ["Error"]);
+    assert!(zelda_out.take().is_empty());
+}
+
+#[test]
+fn bad_names_are_rejected_with_the_rule_broken() {
+    let mut state = state();
+    join(&mut state, "Link");
+
+    let cases = [
+        ("   ", "Name can't be empty."),
+        (" Link2", "Name can't start or end with spaces."),
+        ("L", "Name must be between 2 and 32 characters."),
+        ("Link<3", "Name can't contain '<'"),
+        ("server", "That name is reserved."),
+        ("DEKU BABA", "That name belongs to a monster."),
+        ("link", "Name is already taken by Link."),
+    ];
+
+    for (name, reason) in cases {
+        let (client, outbox) = Client::memory();
+        state.handle_character(client, character(name, 50, 25, 25));
+
+        let out = outbox.take();
+        let [Outbound::Error(err)] = out.as_slice() else {
+            panic!("'{name}' should be rejected");
+        };
+        assert!(err.message.starts_with(reason), "'{name}': {}", err.message);
+    }
+
+    assert_eq!(state.players.len(), 1);
+}
+
+#[test]
+fn kills_grant_xp_and_level_ups_grant_stat_points() {
+    let mut state = state();
+    let (link, outbox) = join(&mut state, "Link");
+
+    state.handle_fight(link.clone(), PktFight::default());
+    assert_eq!(state.progress["Link"].level, 2);
+    assert_eq!(state.progress["Link"].unspent, 5);
+    outbox.take();
+
+    state.handle_character(link.clone(), character("Link", 56, 25, 25));
+    assert_eq!(kinds(&outbox.take()), ["Error"]);
+
+    state.handle_character(link.clone(), character("Link", 49, 30, 25));
+    assert_eq!(kinds(&outbox.take()), ["Error"]);
+
+    state.handle_character(link.clone(), character("Link", 53, 27, 25));
+    assert_eq!(kinds(&outbox.take()), ["Accept", "Character"]);
+    assert_eq!(state.players["Link"].attack, 53);
+    assert_eq!(state.players["Link"].defense, 27);
+    assert_eq!(state.progress["Link"].unspent, 0);
+}
+
+#[test]
+fn xp_is_split_between_everyone_in_battle() {
+    let mut state = state();
+    let (link, _) = join(&mut state, "Link");
+    let (_zelda, _) = join(&mut state, "Zelda");
+    let (_navi, _) = join(&mut state, "Navi");
+
+    state.handle_fight(link, PktFight::default());
+
+    assert_eq!(state.progress["Link"].xp, 4);
+    assert_eq!(state.progress["Zelda"].xp, 3);
+    assert_eq!(state.progress["Navi"].xp, 3);
+}
+
+/// Make a player too weak to hurt the Deku Baba and one bite from death.
+fn doom(state: &mut GameState, name: &str, gold: u16) {
+    let player = state.players.get_mut(name).unwrap();
+    player.attack = 0;
+    player.defense = 0;
+    player.regen = 0;
+    player.health = 2;
+    player.gold = gold;
+}
+
+#[test]
+fn dead_players_drop_a_corpse_and_respawn_after_a_delay() {
+    let mut state = state();
+    let (link, link_out) = join(&mut state, "Link");
+    let (zelda, zelda_out) = join(&mut state, "Zelda");
+    doom(&mut state, "Link", 10);
+    state.players.get_mut("Zelda").unwrap().attack = 0;
+
+    state.handle_fight(link.clone(), PktFight::default());
+    assert!(!state.players["Link"].flags.is_alive());
+    assert_eq!(state.players["Link"].gold, 5);
+
+    let corpse = &state.rooms[&0].monsters.as_ref().unwrap()[1];
+    assert_eq!(corpse.name.as_ref(), "Link's corpse");
+    assert_eq!(corpse.gold, 5);
+    link_out.take();
+
+    state.handle_fight(link.clone(), PktFight::default());
+    state.handle_loot(link.clone(), loot("Link's corpse"));
+    assert_eq!(kinds(&link_out.take()), ["Error", "Error"]);
+
+    zelda_out.take();
+    state.handle_loot(zelda, loot("Link's corpse"));
+    assert_eq!(state.players["Zelda"].gold, 5);
+
+    state.handle_tick();
+    assert!(!state.players["Link"].flags.is_alive());
+
+    state.handle_tick();
+    assert!(state.players["Link"].flags.is_alive());
+    assert_eq!(state.players["Link"].health, 100);
+    assert!(state.rooms[&0].players.contains("Link"));
+}
+
+#[test]
+fn corpses_of_long_names_fit_a_lurk_name() {
+    let mut state = state();
+    let name = "Ganondorf Dragmire Gerudo King";
+    join(&mut state, name);
+    doom(&mut state, name, 10);
+
+    state.kill_player(&Arc::from(name), "a falling rock");
+    let corpse = &state.rooms[&0].monsters.as_ref().unwrap()[1];
+    assert_eq!(corpse.name.as_ref(), "Ganondorf Dragmire Geru's corpse");
+    assert_eq!(corpse.name.len(), 32);
+}
+
+#[test]
+fn nuking_clears_the_dead_and_their_corpses() {
+    let mut state = state();
+    let (link, _) = join(&mut state, "Link");
+    doom(&mut state, "Link", 10);
+    state.kill_player(&Arc::from("Link"), "a falling rock");
+    state.handle_leave(link, PktLeave::default());
+
+    state.handle_command(Action {
+        kind: "nuke".into(),
+        argv: vec!["nuke".into()],
+        operator: "test".into(),
+    });
+    assert!(state.respawns.is_empty());
+    assert_eq!(state.rooms[&0].monsters.as_ref().unwrap().len(), 1);
+}
+
+#[test]
+fn dead_players_can_respawn_on_request() {
+    let mut state = state();
+    let (link, outbox) = join(&mut state, "Link");
+    let change = PktChangeRoom {
+        packet_type: PktType::CHANGEROOM,
+        room_number: 1,
+    };
+    state.handle_change_room(link.clone(), change);
+    state.players.get_mut("Link").unwrap().current_room = 1;
+    doom(&mut state, "Link", 0);
+
+    state.kill_player(&Arc::from("Link"), "a falling rock");
+    assert!(state.rooms[&1].monsters.is_none(), "no gold, no corpse");
+    outbox.take();
+
+    state.handle_message(link.clone(), chat("/respawn"));
+    assert!(state.players["Link"].flags.is_alive());
+    assert_eq!(state.players["Link"].current_room, 0);
+    assert!(!state.rooms[&1].players.contains("Link"));
+    assert!(!state.respawns.contains_key("Link"));
+}
+
+#[test]
+fn fights_hit_the_chosen_target_before_the_weakest() {
+    let mut state = state();
+    let (link, outbox) = join(&mut state, "Link");
+    let monsters = state.rooms.get_mut(&0).unwrap().monsters.as_mut().unwrap();
+    let mut octorok = monsters[0].clone();
+    octorok.name = "Octorok".into();
+    octorok.health = 100;
+    monsters.push(octorok);
+
+    state.handle_message(link.clone(), chat("/target octorok"));
+    assert_eq!(state.targets["Link"].as_ref(), "Octorok");
+    outbox.take();
+
+    state.handle_fight(link.clone(), PktFight::default());
+    let monsters = state.rooms[&0].monsters.as_ref().unwrap();
+    assert_eq!(monsters[0].health, 8);
+    assert_eq!(monsters[1].health, 51);
+
+    state.handle_loot(link.clone(), loot("Deku Baba"));
+    assert_eq!(kinds(&outbox.take()).last(), Some(&"Error"));
+    assert_eq!(state.targets["Link"].as_ref(), "Octorok");
+
+    state.config = Arc::new(Config {
+        target_packet: TargetPacket::Loot,
+        ..(*config()).clone()
+    });
+    state.handle_loot(link.clone(), loot("Deku Baba"));
+    assert_eq!(state.targets["Link"].as_ref(), "Deku Baba");
+
+    state.rooms.get_mut(&0).unwrap().monsters.as_mut().unwrap()[0].health = 0;
+    state.handle_fight(link, PktFight::default());
+    assert_eq!(state.rooms[&0].monsters.as_ref().unwrap()[1].health, 2);
+}
+
+#[test]
+fn monsters_strike_back_at_the_party() {
+    let mut state = state();
+    let (link, _) = join(&mut state, "Link");
+    let (_zelda, _) = join(&mut state, "Zelda");
+    let baba = &mut state.rooms.get_mut(&0).unwrap().monsters.as_mut().unwrap()[0];
+    baba.health = 1000;
+    baba.attack = 30;
+
+    state.handle_fight(link.clone(), PktFight::default());
+    assert_eq!(state.players["Link"].health, 120);
+    assert_eq!(state.players["Zelda"].health, 120);
+
+    state.config = Arc::new(Config {
+        retaliation: Retaliation::Threat,
+        ..(*config()).clone()
+    });
+    state.players.get_mut("Zelda").unwrap().attack = 60;
+
+    state.handle_fight(link, PktFight::default());
+    assert_eq!(state.players["Link"].health, 145, "only regen");
+    assert_eq!(state.players["Zelda"].health, 140);
+}
+
+#[test]
+fn combat_rolls_follow_the_configured_odds_and_seed() {
+    let with_rules = |combat: CombatRules| {
+        let mut state = state();
+        state.config = Arc::new(Config {
+            combat,
+            ..(*config()).clone()
+        });
+        state.reseed(7);
+        state
+    };
+
+    let mut state = with_rules(CombatRules {
+        miss_chance: 1.0,
+        ..CombatRules::default()
+    });
+    let (link, outbox) = join(&mut state, "Link");
+    state.handle_fight(link, PktFight::default());
+    assert_eq!(state.rooms[&0].monsters.as_ref().unwrap()[0].health, 8);
+    assert!(outbox.take().iter().any(|pkt| matches!(
+        pkt,
+        Outbound::Message(msg) if msg.message.as_ref() == "Link misses Deku Baba!"
+    )));
+
+    let mut state = with_rules(CombatRules {
+        crit_chance: 1.0,
+        crit_multiplier: 3.0,
+        ..CombatRules::default()
+    });
+    let (link, _) = join(&mut state, "Link");
+    state.players.get_mut("Link").unwrap().attack = 2;
+    state.handle_fight(link, PktFight::default());
+    assert_eq!(state.rooms[&0].monsters.as_ref().unwrap()[0].health, 5);
+
+    let rules = CombatRules {
+        miss_chance: 0.3,
+        crit_chance: 0.3,
+        crit_multiplier: 2.0,
+        damage_variance: 0.5,
+        ..CombatRules::default()
+    };
+    let rolls = |seed| {
+        let mut rng = rand::SeedableRng::seed_from_u64(seed);
+        (0..20)
+            .map(|_| rules.roll(&mut rng, 40, 10))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(rolls(1), rolls(1));
+    assert!(
+        rolls(1)
+            .iter()
+            .all(|blow| (0..=90).contains(&blow.damage()))
+    );
+}
+
+#[test]
+fn monsters_and_items_apply_status_effects() {
+    let mut state = state();
+    let (link, outbox) = join(&mut state, "Link");
+    let baba = &mut state.rooms.get_mut(&0).unwrap().monsters.as_mut().unwrap()[0];
+    baba.health = 1000;
+    baba.inflicts = vec![Effect {
+        kind: EffectKind::Poison,
+        potency: 10,
+        duration: 2,
+        chance: 1.0,
+    }];
+
+    state.handle_fight(link.clone(), PktFight::default());
+    assert!(state.effects["Link"].has(EffectKind::Poison));
+    assert_eq!(state.players["Link"].health, 125);
+    outbox.take();
+
+    state.handle_tick();
+    state.handle_tick();
+    assert_eq!(state.players["Link"].health, 105);
+    assert!(!state.effects.contains_key("Link"), "poison wore off");
+
+    state.items.get_mut("Red Potion").unwrap().effects = vec![Effect {
+        kind: EffectKind::Stun,
+        potency: 0,
+        duration: 1,
+        chance: 1.0,
+    }];
+    state
+        .inventories
+        .entry("Link".into())
+        .or_default()
+        .items
+        .push("Red Potion".into());
+    state.handle_message(link.clone(), chat("/use red potion"));
+    outbox.take();
+
+    state.handle_fight(link.clone(), PktFight::default());
+    assert_eq!(kinds(&outbox.take()), ["Error"]);
+
+    state.handle_tick();
+    state.handle_fight(link, PktFight::default());
+    assert_ne!(kinds(&outbox.take()), ["Error"]);
+}
+
+#[test]
+fn monsters_wander_and_ambush_newcomers() {
+    let mut state = state();
+    let (link, outbox) = join(&mut state, "Link");
+    let baba = &mut state.rooms.get_mut(&0).unwrap().monsters.as_mut().unwrap()[0];
+    baba.behaviour = Behaviour::Wandering;
+
+    state.handle_tick();
+    assert_eq!(state.rooms[&0].monsters.as_ref().unwrap().len(), 1);
+
+    state.handle_tick();
+    assert!(state.rooms[&0].monsters.as_ref().unwrap().is_empty());
+    let baba = &mut state.rooms.get_mut(&1).unwrap().monsters.as_mut().unwrap()[0];
+    assert_eq!(baba.current_room, 1);
+    assert_eq!(kinds(&outbox.take()), ["Message", "Character"]);
+
+    baba.behaviour = Behaviour::Aggressive;
+    baba.attack = 40;
+    let change = PktChangeRoom {
+        packet_type: PktType::CHANGEROOM,
+        room_number: 1,
+    };
+    state.handle_change_room(link, change);
+    assert_eq!(state.players["Link"].health, 85);
+}
+
+#[test]
+fn loot_is_rolled_on_every_death() {
+    let mut state = state();
+    let (link, _) = join(&mut state, "Link");
+    state.rooms.get_mut(&0).unwrap().monsters.as_mut().unwrap()[0].loot = LootTable {
+        gold_min: 5,
+        gold_max: 9,
+        drops: vec![Drop {
+            item: "Red Potion".into(),
+            chance: 0.0,
+        }],
+    };
+
+    for _ in 0..2 {
+        state.handle_fight(link.clone(), PktFight::default());
+        let baba = &state.rooms[&0].monsters.as_ref().unwrap()[0];
+        assert!((5..=9).contains(&baba.gold));
+        assert!(baba.items.is_empty());
+
+        let gold = state.players["Link"].gold;
+        state.handle_loot(link.clone(), loot("Deku Baba"));
+        assert!(state.players["Link"].gold >= gold + 5);
+
+        state.handle_command(Action {
+            kind: "revive".into(),
+            argv: vec!["revive".into()],
+            operator: "test".into(),
+        });
+    }
+}
+
+#[test]
+fn loot_modes_decide_who_gets_the_gold() {
+    let fight = |mode: LootMode| {
+        let mut state = state();
+        state.config = Arc::new(Config {
+            loot_mode: mode,
+            ..(*config()).clone()
+        });
+        let (link, _) = join(&mut state, "Link");
+        let (zelda, zelda_out) = join(&mut state, "Zelda");
+        state.players.get_mut("Zelda").unwrap().attack = 30;
+        let baba = &mut state.rooms.get_mut(&0).unwrap().monsters.as_mut().unwrap()[0];
+        baba.defense = 0;
+        baba.loot = LootTable::fixed(10, &[]);
+
+        state.handle_fight(link, PktFight::default());
+        zelda_out.take();
+        state.handle_loot(zelda, loot("Deku Baba"));
+        (state, kinds(&zelda_out.take()))
+    };
+
+    let (state, out) = fight(LootMode::KillerOnly);
+    assert_eq!(out, ["Error"]);
+    assert_eq!(state.players["Zelda"].gold, 0);
+
+    let (state, _) = fight(LootMode::SplitByDamage);
+    assert_eq!(state.players["Link"].gold, 6);
+    assert_eq!(state.players["Zelda"].gold, 4);
+
+    let (state, _) = fight(LootMode::RoundRobin);
+    assert_eq!(state.players["Link"].gold, 0);
+    assert_eq!(state.players["Zelda"].gold, 0, "Link's turn");
+}
+
+#[test]
+fn round_robin_keeps_a_turn_per_group() {
+    let mut turns = HashMap::new();
+    let mut next = |group: [&str; 2]| {
+        let group = group.map(Arc::<str>::from);
+        let looters = LootMode::RoundRobin.looters(&group, &group[0], &HashMap::new(), &mut turns);
+        looters[0].to_string()
+    };
+
+    assert_eq!(next(["Zelda", "Link"]), "Link");
+    assert_eq!(
+        next(["Navi", "Zelda"]),
+        "Navi",
+        "a new group starts its own turn"
+    );
+    assert_eq!(next(["Link", "Zelda"]), "Zelda");
+}
+
+#[test]
+fn invites_lapse_when_the_inviter_joins_another_party() {
+    let [link, navi, zelda] = ["Link", "Navi", "Zelda"].map(Arc::<str>::from);
+    let mut parties = Parties::default();
+
+    parties.invite(&link, &navi, 3).unwrap();
+    parties.invite(&zelda, &link, 3).unwrap();
+    parties.accept(&link, 3).unwrap();
+
+    assert!(parties.accept(&navi, 3).is_err());
+    assert!(parties.party_of("Navi").is_none());
+}
+
+#[test]
+fn parties_fight_chat_and_share_loot_together() {
+    let mut state = state();
+    state.config = Arc::new(Config {
+        party_battles: true,
+        ..(*config()).clone()
+    });
+    let (link, link_out) = join(&mut state, "Link");
+    let (zelda, zelda_out) = join(&mut state, "Zelda");
+    let (_navi, navi_out) = join(&mut state, "Navi");
+
+    state.handle_message(zelda.clone(), chat("/party accept"));
+    state.handle_message(link.clone(), chat("/party invite zelda"));
+    state.handle_message(zelda.clone(), chat("/party accept"));
+    assert_eq!(state.parties.members(&"Link".into()).len(), 2);
+    link_out.take();
+    zelda_out.take();
+    navi_out.take();
+
+    state.handle_message(link.clone(), chat("/p to the tree!"));
+    assert_eq!(kinds(&zelda_out.take()), ["Message"]);
+    assert!(navi_out.take().is_empty());
+
+    state.handle_fight(link.clone(), PktFight::default());
+    assert_eq!(state.progress["Link"].xp, 5);
+    assert_eq!(state.progress["Zelda"].xp, 5);
+    assert!(!state.progress.contains_key("Navi"));
+
+    state.handle_loot(zelda.clone(), loot("Deku Baba"));
+    assert_eq!(state.players["Link"].gold, 1);
+    assert_eq!(state.players["Zelda"].gold, 1);
+
+    state.handle_message(zelda, chat("/party leave"));
+    assert!(
+        state.parties.party_of("Link").is_none(),
+        "a party of one disbands"
+    );
+}
+
+#[test]
+fn bosses_change_phase_summon_minions_and_announce_defeat() {
+    let world = map::parse(
+        r#"[{
+            "room_number": 0,
+            "title": "Lair",
+            "connections": {},
+            "desc": "Bones everywhere.",
+            "players": [],
+            "monsters": [{
+                "name": "Gohma",
+                "current_room": 0,
+                "max_health": 200,
+                "health": 200,
+                "attack": 1,
+                "defense": 0,
+                "gold": 0,
+                "desc": "A giant eye.",
+                "boss": { "phases": [
+                    { "below": 80, "narration": "Gohma climbs the wall!", "defense": 10,
+                      "minions": [{ "name": "Gohma Larva", "health": 5, "attack": 1, "defense": 0, "desc": "An egg." }] },
+                    { "below": 30, "narration": "Gohma is enraged!", "attack": 20 }
+                ]}
+            }]
+        }]"#,
+    )
+    .expect("boss map parses");
+    let mut state = GameState::new(world, config());
+    let (link, outbox) = join(&mut state, "Link");
+    let (_zelda, zelda_out) = join(&mut state, "Zelda");
+    state.players.get_mut("Link").unwrap().attack = 60;
+    state.players.get_mut("Zelda").unwrap().attack = 0;
+    state.players.get_mut("Zelda").unwrap().current_room = 1; // Out of the fight, but online
+
+    let gohma = |state: &GameState| state.rooms[&0].monsters.as_ref().unwrap()[0].clone();
+
+    state.handle_fight(link.clone(), PktFight::default());
+    assert_eq!((gohma(&state).health, gohma(&state).defense), (140, 10));
+    assert_eq!(
+        state.rooms[&0].monsters.as_ref().unwrap()[1].name.as_ref(),
+        "Gohma Larva"
+    );
+    state.handle_message(link.clone(), chat("/target gohma"));
+    outbox.take();
+
+    state.handle_fight(link.clone(), PktFight::default());
+    state.handle_fight(link.clone(), PktFight::default());
+    assert_eq!(gohma(&state).attack, 20);
+
+    state.handle_fight(link.clone(), PktFight::default());
+    assert!(gohma(&state).health <= 0);
+    assert!(zelda_out.take().iter().any(|pkt| matches!(
+        pkt,
+        Outbound::Message(msg) if msg.message.as_ref() == "Gohma has been defeated by Link!"
+    )));
+
+    state.handle_command(Action {
+        kind: "revive".into(),
+        argv: vec!["revive".into()],
+        operator: "test".into(),
+    });
+    assert_eq!((gohma(&state).attack, gohma(&state).defense), (1, 0));
+}
@@ -1,13 +1,16 @@
 use clap::Parser;
+use std::fs::File;
+use std::net::{SocketAddr, TcpListener};
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex, mpsc};
-use std::{fs::File, net::TcpListener};
-use time::{UtcOffset, format_description::parse};
 use tracing::{debug, info, warn};
-use tracing_subscriber::fmt::time::OffsetTime;
 
+use crate::logic::config::LogConfig;
+use crate::logic::record::{Recorder, replay};
 use crate::logic::{Config, GameSender, commands::input, map};
-use crate::threads::{connection, server};
+use crate::threads::{connection, metrics, server, ticker, tls};
 
+pub mod logging;
 pub mod logic;
 pub mod threads;
 
@@ -17,6 +20,16 @@ struct Args {
     /// Port to bind the TCP Connection
     #[arg(short, long, default_value_t = 5051)]
     port: u16,
+    /// Port to accept TLS connections on; requires TLS_CERT_FILEPATH and TLS_KEY_FILEPATH
+    #[arg(long)]
+    tls_port: Option<u16>,
+    /// Write every dispatched packet and command to this file for later replay, creating or
+    /// truncating it
+    #[arg(long, value_name = "FILE")]
+    record: Option<PathBuf>,
+    /// Replay a recording against a fresh game, print the packets sent, and exit
+    #[arg(long, value_name = "FILE", conflicts_with = "record")]
+    replay: Option<PathBuf>,
     #[command(flatten)]
     verbosity: clap_verbosity_flag::Verbosity,
 }
@@ -27,45 +40,89 @@ fn main() -> ! {
     dotenvy::from_filename(".env.local").expect("Failed to load .env.local file");
 
     // Setup tracing subscriber for logging
-    let timer = parse("[year]-[month padding:zero]-[day padding:zero] [hour]:[minute]:[second]")
-        .expect("Tracing time format is invalid");
-    let time_offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
-    let timer = OffsetTime::new(time_offset, timer);
-
-    tracing_subscriber::fmt()
-        .with_max_level(args.verbosity)
-        .with_line_number(true)
-        .with_target(false)
-        .with_timer(timer)
-        .with_file(true)
-        .with_ansi(true)
-        .compact()
-        .init();
+    let log_config = LogConfig::load();
+    logging::init(
+        args.verbosity.into(),
+        &log_config,
+        &format!("{}_serverlog", args.port),
+    );
 
     // Load server and client configurations
     let server_config = Arc::new(Config::load());
     let client_config = server_config.clone(); // The Arc will handle all reference counting, it's not actually cloning all the data :)
 
+    // Build the game map
+    let file = File::open(&server_config.map_path).expect("Failed to open map file!");
+    let world = map::build(file).expect("Failed to build map from file");
+
+    info!("Parsed map successfully");
+
+    if let Some(path) = &args.replay {
+        replay(path, world, server_config).expect("Failed to replay recording");
+        std::process::exit(0);
+    }
+
+    let recorder = args
+        .record
+        .as_deref()
+        .map(|path| Recorder::create(path).expect("Failed to open recording file"));
+
     let address = format!("0.0.0.0:{}", args.port);
     let listener = TcpListener::bind(&address).expect("Failed to bind to address");
 
     info!("Listening on {address}");
 
+    if let Some(tls_port) = args.tls_port {
+        let cert_path = server_config
+            .tls_cert_path
+            .as_ref()
+            .expect("TLS_CERT_FILEPATH must be set to use TLS.");
+        let key_path = server_config
+            .tls_key_path
+            .as_ref()
+            .expect("TLS_KEY_FILEPATH must be set to use TLS.");
+        let tls_config = tls::load_config(cert_path, key_path);
+
+        let tls_address = format!("0.0.0.0:{}", tls_port);
+        let tls_listener = TcpListener::bind(&tls_address).expect("Failed to bind to TLS address");
+        let upstream = SocketAddr::from(([127, 0, 0, 1], args.port));
+
+        info!("Listening for TLS on {tls_address}");
+
+        let _ = std::thread::spawn(move || {
+            info!("Started TLS thread!");
+            tls(tls_listener, tls_config, upstream);
+        });
+    }
+
+    if let Some(metrics_addr) = &server_config.metrics_addr {
+        let metrics_listener =
+            TcpListener::bind(metrics_addr.as_ref()).expect("Failed to bind to metrics address");
+
+        info!("Serving metrics on http://{metrics_addr}/metrics");
+
+        let _ = std::thread::spawn(move || {
+            info!("Started metrics thread!");
+            metrics(metrics_listener);
+        });
+    }
+
     // Create a channel for communication between threads
     let (tx, rx) = mpsc::channel();
     let sender = tx.clone();
     let receiver = Arc::new(Mutex::new(rx));
 
-    // Build the game map
-    let file = File::open(&server_config.map_path).expect("Failed to open map file!");
-    let rooms = map::build(file).expect("Failed to build map from file");
-
     // Start the server and command input threads
-    info!("Parsed map successfully");
-
     let _ = std::thread::spawn(move || {
         info!("Started server thread!");
-        server(receiver, server_config, rooms);
+        server(receiver, server_config, world, recorder);
+    });
+
+    let tick_sender = GameSender(sender.clone());
+
+    let _ = std::thread::spawn(move || {
+        info!("Started ticker thread!");
+        ticker(tick_sender);
     });
 
     let input_prefix = client_config.cmd_prefix.clone().into_string();
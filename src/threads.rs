@@ -1,5 +1,11 @@
 pub use crate::threads::connection::connection;
+pub use crate::threads::metrics::metrics;
 pub use crate::threads::server::server;
+pub use crate::threads::ticker::ticker;
+pub use crate::threads::tls::tls;
 
 pub mod connection;
+pub mod metrics;
 pub mod server;
+pub mod ticker;
+pub mod tls;
@@ -5,7 +5,8 @@ use std::net::TcpStream;
 use std::sync::Arc;
 use tracing::{error, info, warn};
 
-use crate::logic::{Config, GameSender};
+use crate::logic::metrics::METRICS;
+use crate::logic::{Client, Config, GameSender};
 
 pub fn connection(stream: Arc<TcpStream>, sender: GameSender, config: Arc<Config>) {
     // Send the initial game info to the client
@@ -31,13 +32,18 @@ pub fn connection(stream: Arc<TcpStream>, sender: GameSender, config: Arc<Config
         }
     );
 
+    let client = Client::tcp(stream.clone());
+
+    METRICS.observe_raw_outbound("Version", 5);
+    METRICS.observe_raw_outbound("Game", 7 + config.description.len());
+
     // Main loop to read packets from the client
     loop {
         match Protocol::recv(&stream) {
             Ok(pkt) => {
                 info!("Packet read successfully");
 
-                sender.send_base(pkt);
+                sender.send_base(client.clone(), pkt);
                 continue; // Don't fallout to graceful exit
             }
             Err(e) => {
@@ -54,7 +60,7 @@ pub fn connection(stream: Arc<TcpStream>, sender: GameSender, config: Arc<Config
         };
 
         // Exit gracefully
-        sender.send_base(Protocol::Leave(stream.clone(), PktLeave::default()));
+        sender.send_base(client, Protocol::Leave(stream.clone(), PktLeave::default()));
         break;
     }
 
@@ -0,0 +1,58 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::logic::metrics::METRICS;
+
+/// Serve the metrics in Prometheus text format over a minimal HTTP/1.1 endpoint.
+/// Requests are handled inline since scrapes are infrequent and cheap to answer.
+pub fn metrics(listener: TcpListener) -> ! {
+    loop {
+        match listener.accept() {
+            Ok((stream, addr)) => {
+                debug!("Metrics request from {}", addr);
+
+                if let Err(e) = respond(stream) {
+                    warn!("Failed to answer metrics request: {}", e);
+                }
+            }
+            Err(e) => {
+                warn!("Error accepting metrics connection: {}", e);
+            }
+        }
+    }
+}
+
+fn respond(stream: TcpStream) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    // Drain the headers; we don't need any of them
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+
+    let (status, body) = if path == "/metrics" {
+        ("200 OK", METRICS.render())
+    } else {
+        ("404 Not Found", String::from("Not found; try /metrics\n"))
+    };
+
+    let mut stream = &stream;
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\n\
+         Content-Type: text/plain; version=0.0.4\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
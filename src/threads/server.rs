@@ -1,17 +1,22 @@
 use lurk_lcsc::Protocol;
-use std::collections::HashMap;
 use std::sync::{Arc, Mutex, mpsc::Receiver};
 use std::time::Instant;
 use tracing::{debug, warn};
 
-use crate::logic::{Config, ExtendedProtocol, GameState, Room};
+use crate::logic::metrics::{self, METRICS};
+use crate::logic::{Config, ExtendedProtocol, GameState, World, record::Recorder};
 
 pub fn server(
     receiver: Arc<Mutex<Receiver<ExtendedProtocol>>>,
     config: Arc<Config>,
-    rooms: HashMap<u16, Room>,
+    world: World,
+    mut recorder: Option<Recorder>,
 ) -> ! {
-    let mut state = GameState::new(rooms, config);
+    let mut state = GameState::new(world, config);
+
+    if let Some(recorder) = recorder.as_mut() {
+        recorder.record_seed(state.seed);
+    }
 
     loop {
         let packet = match receiver.lock().unwrap().recv() {
@@ -22,37 +27,45 @@ pub fn server(
             }
         };
 
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.record(&packet, state.tick);
+        }
+
+        let described = metrics::describe(&packet);
         let start = Instant::now();
 
         match packet {
-            ExtendedProtocol::Base(Protocol::Message(author, content)) => {
+            ExtendedProtocol::Base(author, Protocol::Message(_, content)) => {
                 state.handle_message(author, content);
             }
-            ExtendedProtocol::Base(Protocol::ChangeRoom(author, content)) => {
+            ExtendedProtocol::Base(author, Protocol::ChangeRoom(_, content)) => {
                 state.handle_change_room(author, content);
             }
-            ExtendedProtocol::Base(Protocol::Fight(author, content)) => {
+            ExtendedProtocol::Base(author, Protocol::Fight(_, content)) => {
                 state.handle_fight(author, content);
             }
-            ExtendedProtocol::Base(Protocol::PVPFight(author, content)) => {
+            ExtendedProtocol::Base(author, Protocol::PVPFight(_, content)) => {
                 state.handle_pvp_fight(author, content);
             }
-            ExtendedProtocol::Base(Protocol::Loot(author, content)) => {
+            ExtendedProtocol::Base(author, Protocol::Loot(_, content)) => {
                 state.handle_loot(author, content);
             }
-            ExtendedProtocol::Base(Protocol::Start(author, content)) => {
+            ExtendedProtocol::Base(author, Protocol::Start(_, content)) => {
                 state.handle_start(author, content);
             }
-            ExtendedProtocol::Base(Protocol::Character(author, content)) => {
+            ExtendedProtocol::Base(author, Protocol::Character(_, content)) => {
                 state.handle_character(author, content);
             }
-            ExtendedProtocol::Base(Protocol::Leave(author, content)) => {
+            ExtendedProtocol::Base(author, Protocol::Leave(_, content)) => {
                 state.handle_leave(author, content);
             }
-            ExtendedProtocol::Base(_) => {} // Ignore all other packets
+            ExtendedProtocol::Base(_, _) => {} // Ignore all other packets
             ExtendedProtocol::Command(action) => {
                 state.handle_command(action);
             }
+            ExtendedProtocol::Tick => {
+                state.handle_tick();
+            }
         }
 
         let end = Instant::now();
@@ -61,5 +74,10 @@ pub fn server(
         let nanos = delta.subsec_nanos();
 
         debug!("Took: {secs}.{nanos} seconds to process packet.");
+
+        if let Some((kind, len)) = described {
+            METRICS.observe_inbound(kind, len, delta);
+        }
+        METRICS.observe_state(&state);
     }
 }
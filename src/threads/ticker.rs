@@ -0,0 +1,14 @@
+use std::time::Duration;
+
+use crate::logic::GameSender;
+
+/// How often the game advances timed events such as respawns.
+pub const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Send the server thread a tick at a fixed interval, forever.
+pub fn ticker(sender: GameSender) -> ! {
+    loop {
+        std::thread::sleep(TICK_INTERVAL);
+        sender.send_tick();
+    }
+}
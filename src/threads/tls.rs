@@ -0,0 +1,108 @@
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::{ServerConfig, ServerConnection, StreamOwned};
+use std::io::ErrorKind::{Interrupted, TimedOut, WouldBlock};
+use std::io::{Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// How long each side of the relay blocks on a read before polling the other side.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Load the certificate chain and private key into a rustls server config.
+pub fn load_config(cert_path: &Path, key_path: &Path) -> Arc<ServerConfig> {
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .expect("Failed to open TLS certificate file!")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("Failed to parse TLS certificate file");
+    let key = PrivateKeyDer::from_pem_file(key_path).expect("Failed to parse TLS key file");
+
+    let config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .expect("TLS certificate and key do not match");
+
+    Arc::new(config)
+}
+
+/// Accept TLS clients and relay their decrypted traffic to the plaintext listener at
+/// `upstream`. The Lurk codec reads straight from a `TcpStream`, so terminating here keeps
+/// TLS and plaintext clients on the exact same connection path.
+pub fn tls(listener: TcpListener, config: Arc<ServerConfig>, upstream: SocketAddr) -> ! {
+    loop {
+        match listener.accept() {
+            Ok((stream, addr)) => {
+                info!("New TLS connection: {}", addr);
+
+                let config = config.clone();
+
+                let relay_h = std::thread::spawn(move || {
+                    if let Err(e) = relay(stream, config, upstream) {
+                        warn!("TLS relay for {} closed: {}", addr, e);
+                    }
+                });
+
+                debug!("Spawned TLS relay thread: {:?}", relay_h.thread().id());
+            }
+            Err(e) => {
+                warn!("Error accepting TLS connection: {}", e);
+            }
+        }
+    }
+}
+
+/// Pump bytes between one TLS client and a fresh plaintext connection until either closes.
+fn relay(
+    stream: TcpStream,
+    config: Arc<ServerConfig>,
+    upstream: SocketAddr,
+) -> std::io::Result<()> {
+    let conn = ServerConnection::new(config).map_err(std::io::Error::other)?;
+    let mut plain = TcpStream::connect(upstream)?;
+
+    stream.set_read_timeout(Some(POLL_INTERVAL))?;
+    plain.set_read_timeout(Some(POLL_INTERVAL))?;
+
+    let mut secure = StreamOwned::new(conn, stream);
+    let mut buf = [0u8; 4096];
+
+    let result = loop {
+        // Client -> server
+        match secure.read(&mut buf) {
+            Ok(0) => break Ok(()),
+            Ok(n) => {
+                if let Err(e) = plain.write_all(&buf[..n]) {
+                    break Err(e);
+                }
+            }
+            Err(e) if matches!(e.kind(), WouldBlock | TimedOut | Interrupted) => {}
+            Err(e) => break Err(e),
+        }
+
+        // Server -> client
+        match plain.read(&mut buf) {
+            Ok(0) => break Ok(()),
+            Ok(n) => {
+                if let Err(e) = secure.write_all(&buf[..n]).and_then(|_| secure.flush()) {
+                    break Err(e);
+                }
+            }
+            Err(e) if matches!(e.kind(), WouldBlock | TimedOut | Interrupted) => {}
+            Err(e) => break Err(e),
+        }
+    };
+
+    secure.conn.send_close_notify();
+    let _ = secure.flush();
+
+    if let Err(e) = plain.shutdown(Shutdown::Both) {
+        error!("Failed to shutdown relay connection: {}", e);
+    }
+
+    info!("TLS relay exiting.");
+
+    result
+}
@@ -49,7 +49,7 @@ cargo build --release
 
 while true
 do
-    stdbuf -oL ./target/release/ZeldaServer --port ${1:-8080} ${2:--vv} 2>&1 | tee -a ./logs/${1:-8080}_serverlog_`date +%m_%d_%y_%s`.log
+    LOG_DIR=${LOG_DIR:-./logs} ./target/release/ZeldaServer --port ${1:-8080} ${2:--vv}
     echo CRASH at `date` | tee -a ./logs/${1:-8080}_lurk_crash.log
     sleep 120
 done
@@ -0,0 +1,239 @@
+#![allow(dead_code)] // Each test binary only uses part of the harness
+
+use lurk_lcsc::{
+    CharacterFlags, PktAccept, PktChangeRoom, PktCharacter, PktConnection, PktError, PktFight,
+    PktGame, PktLeave, PktLoot, PktMessage, PktPVPFight, PktRoom, PktStart, PktType, PktVersion,
+    Protocol, send_to,
+};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long a client waits for a packet before failing the test.
+const RECV_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long to wait for the server to start accepting connections.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A server process bound to an ephemeral port, killed when dropped.
+pub struct Server {
+    pub port: u16,
+    child: Child,
+    // Held open so the command input thread blocks instead of spinning on EOF
+    _stdin: ChildStdin,
+    workdir: PathBuf,
+}
+
+impl Server {
+    /// Start the server against the fixture map and description.
+    pub fn start() -> Self {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let port = free_port();
+
+        let workdir = std::env::temp_dir().join(format!("zelda-server-test-{port}"));
+        std::fs::create_dir_all(&workdir).expect("Failed to create test workdir");
+
+        let env = format!(
+            "CMD_PREFIX = \"!\"\n\
+             MAJOR_REV = \"2\"\n\
+             MINOR_REV = \"3\"\n\
+             STAT_LIMIT = \"65535\"\n\
+             INITIAL_POINTS = \"100\"\n\
+             COMBAT_SEED = \"0\"\n\
+             MISS_CHANCE = \"0\"\n\
+             CRIT_CHANCE = \"0\"\n\
+             DAMAGE_VARIANCE = \"0\"\n\
+             MAP_FILEPATH = \"{}\"\n\
+             DESC_FILEPATH = \"{}\"\n",
+            fixtures.join("map.json").display(),
+            fixtures.join("desc.txt").display(),
+        );
+        std::fs::write(workdir.join(".env.local"), env).expect("Failed to write .env.local");
+
+        let mut child = Command::new(env!("CARGO_BIN_EXE_ZeldaServer"))
+            .args(["--port", &port.to_string()])
+            .current_dir(&workdir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Failed to spawn server");
+
+        let stdin = child.stdin.take().expect("stdin is piped");
+
+        let deadline = Instant::now() + STARTUP_TIMEOUT;
+        while TcpStream::connect(("127.0.0.1", port)).is_err() {
+            assert!(Instant::now() < deadline, "Server never started listening");
+            std::thread::sleep(Duration::from_millis(50));
+        }
+
+        Self {
+            port,
+            child,
+            _stdin: stdin,
+            workdir,
+        }
+    }
+
+    /// Connect a new client and consume the Version and Game greeting.
+    pub fn connect(&self) -> LurkClient {
+        let client = LurkClient::connect(self.port);
+
+        client.expect_version();
+        client.expect_game();
+
+        client
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.workdir);
+    }
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .expect("Failed to find a free port")
+}
+
+/// Expect the next packet to be a given `Protocol` variant and return its contents.
+macro_rules! expect_variant {
+    ($name:ident, $variant:ident, $pkt:ty) => {
+        pub fn $name(&self) -> $pkt {
+            match self.recv() {
+                Protocol::$variant(_, pkt) => pkt,
+                _ => panic!("Expected {} packet", stringify!($variant)),
+            }
+        }
+    };
+}
+
+/// A scripted headless Lurk client.
+pub struct LurkClient {
+    stream: Arc<TcpStream>,
+}
+
+impl LurkClient {
+    pub fn connect(port: u16) -> Self {
+        let stream = TcpStream::connect(("127.0.0.1", port)).expect("Failed to connect");
+        stream
+            .set_read_timeout(Some(RECV_TIMEOUT))
+            .expect("Failed to set read timeout");
+
+        Self {
+            stream: Arc::new(stream),
+        }
+    }
+
+    pub fn recv(&self) -> Protocol {
+        Protocol::recv(&self.stream).expect("Failed to receive packet")
+    }
+
+    expect_variant!(expect_accept, Accept, PktAccept);
+    expect_variant!(expect_character, Character, PktCharacter);
+    expect_variant!(expect_connection, Connection, PktConnection);
+    expect_variant!(expect_error, Error, PktError);
+    expect_variant!(expect_game, Game, PktGame);
+    expect_variant!(expect_message, Message, PktMessage);
+    expect_variant!(expect_room, Room, PktRoom);
+    expect_variant!(expect_version, Version, PktVersion);
+
+    pub fn send_character(&self, name: &str, attack: u16, defense: u16, regen: u16) {
+        let pkt = PktCharacter {
+            author: None,
+            packet_type: PktType::CHARACTER,
+            name: name.into(),
+            flags: CharacterFlags::empty(),
+            attack,
+            defense,
+            regen,
+            health: 0,
+            gold: 0,
+            current_room: 0,
+            description_len: 11,
+            description: "A test hero".into(),
+        };
+
+        send_to(&self.stream, &pkt).expect("Failed to send Character");
+    }
+
+    pub fn send_start(&self) {
+        send_to(&self.stream, &PktStart::default()).expect("Failed to send Start");
+    }
+
+    pub fn send_fight(&self) {
+        send_to(&self.stream, &PktFight::default()).expect("Failed to send Fight");
+    }
+
+    pub fn send_pvp_fight(&self, target: &str) {
+        let pkt = PktPVPFight {
+            packet_type: PktType::PVPFIGHT,
+            target_name: target.into(),
+        };
+
+        send_to(&self.stream, &pkt).expect("Failed to send PVPFight");
+    }
+
+    pub fn send_loot(&self, target: &str) {
+        let pkt = PktLoot {
+            packet_type: PktType::LOOT,
+            target_name: target.into(),
+        };
+
+        send_to(&self.stream, &pkt).expect("Failed to send Loot");
+    }
+
+    pub fn send_change_room(&self, room_number: u16) {
+        let pkt = PktChangeRoom {
+            packet_type: PktType::CHANGEROOM,
+            room_number,
+        };
+
+        send_to(&self.stream, &pkt).expect("Failed to send ChangeRoom");
+    }
+
+    pub fn send_message(&self, recipient: &str, sender: &str, message: &str) {
+        let mut pkt = PktMessage::server(recipient, message);
+        pkt.sender = sender.into();
+
+        send_to(&self.stream, &pkt).expect("Failed to send Message");
+    }
+
+    pub fn send_leave(&self) {
+        send_to(&self.stream, &PktLeave::default()).expect("Failed to send Leave");
+    }
+
+    /// Expect the server to have closed the connection.
+    pub fn expect_closed(&self) {
+        assert!(
+            Protocol::recv(&self.stream).is_err(),
+            "Expected the connection to be closed"
+        );
+    }
+
+    /// Create a character and start the game, checking the response sequence up to the
+    /// starting room's connections. The room contents that follow are left to the caller
+    /// since they depend on who else is playing. Returns the started character.
+    pub fn join(&self, name: &str) -> PktCharacter {
+        self.send_character(name, 50, 25, 25);
+        self.expect_accept();
+        assert_eq!(self.expect_character().name.as_ref(), name);
+
+        self.send_start();
+        let me = self.expect_character();
+        assert_eq!(me.name.as_ref(), name);
+        assert!(me.flags.is_started());
+
+        self.expect_message(); // "<name> has started the game!"
+        assert_eq!(self.expect_room().room_number, 0);
+        assert_eq!(self.expect_connection().room_number, 1);
+
+        me
+    }
+}
@@ -0,0 +1,135 @@
+use lurk_lcsc::LurkError;
+
+mod common;
+
+use common::Server;
+
+#[test]
+fn single_player_adventure() {
+    let server = Server::start();
+    let link = server.connect();
+
+    let me = link.join("Link");
+    assert_eq!(link.expect_character().name.as_ref(), "Link");
+
+    let baba = link.expect_character();
+    assert_eq!(baba.name.as_ref(), "Deku Baba");
+    assert_eq!(baba.health, 8);
+    assert!(baba.flags.is_alive());
+
+    // Fight: the player is updated, then the monster
+    link.send_fight();
+    assert_eq!(link.expect_character().name.as_ref(), "Link");
+
+    let baba = link.expect_character();
+    assert_eq!(baba.name.as_ref(), "Deku Baba");
+    assert!(baba.health <= 0);
+
+    // Loot: the player gains the monster's gold, then the emptied monster
+    link.send_loot("Deku Baba");
+    assert_eq!(link.expect_character().gold, me.gold + 2);
+    assert_eq!(link.expect_character().gold, 0);
+
+    link.send_loot("Deku Baba");
+    assert!(matches!(link.expect_error().error, LurkError::BADMONSTER));
+
+    link.send_loot("Ganon");
+    assert!(matches!(link.expect_error().error, LurkError::BADMONSTER));
+
+    // Movement
+    link.send_change_room(0);
+    assert!(matches!(link.expect_error().error, LurkError::BADROOM));
+
+    link.send_change_room(5);
+    assert!(matches!(link.expect_error().error, LurkError::BADROOM));
+
+    link.send_change_room(1);
+    assert_eq!(link.expect_room().room_number, 1);
+    assert_eq!(link.expect_character().current_room, 1);
+    assert_eq!(link.expect_character().name.as_ref(), "Link"); // New room alert
+    assert_eq!(link.expect_connection().room_number, 0);
+    assert_eq!(link.expect_character().name.as_ref(), "Link"); // Room contents
+
+    link.send_fight();
+    assert!(matches!(link.expect_error().error, LurkError::NOFIGHT));
+
+    link.send_pvp_fight("Zelda");
+    assert!(matches!(
+        link.expect_error().error,
+        LurkError::NOPLAYERCOMBAT
+    ));
+
+    link.send_leave();
+    link.expect_closed();
+}
+
+#[test]
+fn players_see_and_message_each_other() {
+    let server = Server::start();
+    let link = server.connect();
+    let zelda = server.connect();
+
+    link.join("Link");
+    assert_eq!(link.expect_character().name.as_ref(), "Link");
+    assert_eq!(link.expect_character().name.as_ref(), "Deku Baba");
+
+    zelda.join("Zelda");
+    assert_eq!(zelda.expect_character().name.as_ref(), "Link");
+    assert_eq!(zelda.expect_character().name.as_ref(), "Zelda");
+    assert_eq!(zelda.expect_character().name.as_ref(), "Deku Baba");
+
+    // Link sees Zelda arrive, then the start broadcast
+    assert_eq!(link.expect_character().name.as_ref(), "Zelda");
+    assert!(link.expect_message().message.contains("Zelda has started"));
+
+    // Direct messages
+    zelda.send_message("Link", "Zelda", "Hey, listen!");
+    let msg = link.expect_message();
+    assert_eq!(msg.sender.as_ref(), "Zelda");
+    assert_eq!(msg.message.as_ref(), "Hey, listen!");
+
+    zelda.send_message("Ganon", "Zelda", "Hello?");
+    assert!(matches!(zelda.expect_error().error, LurkError::OTHER));
+
+    // Zelda is alerted when Link leaves the room
+    link.send_change_room(1);
+    assert_eq!(link.expect_room().room_number, 1);
+
+    let alert = zelda.expect_character();
+    assert_eq!(alert.name.as_ref(), "Link");
+    assert_eq!(alert.current_room, 1);
+
+    // Names of started players cannot be taken
+    let impostor = server.connect();
+    impostor.send_character("Link", 10, 10, 10);
+    assert!(matches!(
+        impostor.expect_error().error,
+        LurkError::PLAYEREXISTS
+    ));
+
+    // Leaving is broadcast to everyone still playing
+    zelda.send_leave();
+    zelda.expect_closed();
+
+    // Skip the rest of Link's move into the hall
+    link.expect_character();
+    link.expect_character();
+    link.expect_connection();
+    link.expect_character();
+
+    assert!(link.expect_message().message.contains("Zelda has left"));
+}
+
+#[test]
+fn invalid_stats_are_rejected() {
+    let server = Server::start();
+    let client = server.connect();
+
+    client.send_character("Link", 100, 100, 100);
+    assert!(matches!(client.expect_error().error, LurkError::STATERROR));
+
+    // A valid retry is still accepted
+    client.send_character("Link", 40, 30, 30);
+    client.expect_accept();
+    assert_eq!(client.expect_character().name.as_ref(), "Link");
+}